@@ -0,0 +1,118 @@
+/// Contraction of mutation trees into clone trees.
+use crate::tree::{Node, Tree, TreeError};
+use std::collections::HashMap;
+
+/// Contracts groups of nodes sharing a partition id into single clone nodes.
+/// Nodes which are not in `partition` form single-node groups.
+///
+/// Each group has to be connected, i.e., it has to have a single top node
+/// (a member whose parent is not in the group) and then all the other members
+/// are its descendants connected through the group. The contracted node
+/// keeps the label of the top node, and the children of all the members
+/// (which are not in the group) become its children.
+///
+/// Returns `TopologyError` if some group is not connected.
+pub fn contract_by_partition(
+    tree: &Tree,
+    partition: &HashMap<Node, usize>,
+) -> Result<Tree, TreeError> {
+    let same_group = |a: Node, b: Node| match (partition.get(&a), partition.get(&b)) {
+        (Some(x), Some(y)) => x == y,
+        _ => false,
+    };
+
+    // Nodes in pre-order, so that parents are visited before children.
+    let order = tree.preorder();
+
+    // Map each node to the top node of its group, checking that each group has one top.
+    let mut representative: HashMap<Node, Node> = HashMap::new();
+    let mut tops: HashMap<usize, Node> = HashMap::new();
+    for &node in order.iter() {
+        let top = match tree.get_parent(node) {
+            Some(parent) if same_group(node, parent) => representative[&parent],
+            _ => node,
+        };
+        if top == node {
+            if let Some(&group) = partition.get(&node) {
+                if tops.insert(group, node).is_some() {
+                    return Err(TreeError::TopologyError);
+                }
+            }
+        }
+        representative.insert(node, top);
+    }
+
+    let mut contracted = Tree::new(tree.get_root());
+    for &node in order.iter() {
+        if let Some(parent) = tree.get_parent(node) {
+            if representative[&node] == node {
+                contracted.add_node(representative[&parent], node)?;
+            }
+        }
+    }
+    Ok(contracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::create_chain_tree;
+
+    #[test]
+    fn chain_of_three_mutations() {
+        let tree = create_chain_tree([0, 1, 2, 3, 4]).unwrap();
+        let partition = HashMap::from([(1, 7), (2, 7), (3, 7)]);
+
+        let contracted = contract_by_partition(&tree, &partition).unwrap();
+        assert_eq!(contracted, create_chain_tree([0, 1, 4]).unwrap());
+    }
+
+    /// Generates a tree
+    /// 0–1–2–3
+    ///   │ └─5
+    ///   └─4
+    fn branching_tree() -> Tree {
+        let mut tree = create_chain_tree([0, 1, 2, 3]).unwrap();
+        tree.add_node(1, 4).unwrap();
+        tree.add_node(2, 5).unwrap();
+        tree
+    }
+
+    #[test]
+    fn disconnected_group() {
+        // Nodes 3 and 5 are siblings, so their group is not connected.
+        let partition = HashMap::from([(1, 0), (2, 0), (3, 1), (5, 1)]);
+        assert!(matches!(
+            contract_by_partition(&branching_tree(), &partition),
+            Err(TreeError::TopologyError)
+        ));
+    }
+
+    #[test]
+    fn children_of_members_are_kept() {
+        let tree = branching_tree();
+        let partition = HashMap::from([(1, 0), (2, 0)]);
+        let contracted = contract_by_partition(&tree, &partition).unwrap();
+        let mut expected = Tree::new(0);
+        expected.add_node(0, 1).unwrap();
+        expected.add_node(1, 3).unwrap();
+        expected.add_node(1, 4).unwrap();
+        expected.add_node(1, 5).unwrap();
+        assert_eq!(contracted, expected);
+    }
+
+    #[test]
+    fn group_with_root() {
+        let tree = create_chain_tree([0, 1, 2]).unwrap();
+        let partition = HashMap::from([(0, 3), (1, 3)]);
+
+        let contracted = contract_by_partition(&tree, &partition).unwrap();
+        assert_eq!(contracted, create_chain_tree([0, 2]).unwrap());
+    }
+
+    #[test]
+    fn empty_partition() {
+        let tree = create_chain_tree([0, 1, 2]).unwrap();
+        assert_eq!(contract_by_partition(&tree, &HashMap::new()).unwrap(), tree);
+    }
+}
@@ -0,0 +1,42 @@
+/// Export of trees to array-based formats.
+use crate::tree::metrics::compact_labels;
+use crate::tree::{Node, Tree};
+use std::collections::HashMap;
+
+/// Returns the parent array of the tree, indexed by compacted labels
+/// (see `compact_labels`), together with the map from labels to indices.
+/// The entry of each node is the compacted index of its parent,
+/// and the entry of the root is `-1`.
+pub fn to_compact_parent_array(tree: &Tree) -> (Vec<i64>, HashMap<Node, usize>) {
+    let index = compact_labels(tree);
+    let mut parents = vec![-1; index.len()];
+    for (parent, child) in tree.edges() {
+        parents[index[&child]] = index[&parent] as i64;
+    }
+    (parents, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::create_chain_tree;
+
+    #[test]
+    fn parent_array() {
+        // Labels 3, 7, 10, 20, 40 are compacted to 0, 1, 2, 3, 4.
+        let mut tree = create_chain_tree([10, 3, 40]).unwrap();
+        tree.add_node(10, 20).unwrap();
+        tree.add_node(20, 7).unwrap();
+
+        let (parents, index) = to_compact_parent_array(&tree);
+        assert_eq!(parents, vec![2, 3, -1, 2, 0]);
+        assert_eq!(parents[index[&10]], -1);
+        assert_eq!(index[&40], 4);
+    }
+
+    #[test]
+    fn single_node() {
+        let (parents, _) = to_compact_parent_array(&Tree::new(5));
+        assert_eq!(parents, vec![-1]);
+    }
+}
@@ -0,0 +1,13 @@
+mod clones;
+mod export;
+mod newick;
+mod prior;
+mod summary;
+mod validation;
+
+pub use clones::contract_by_partition;
+pub use export::to_compact_parent_array;
+pub use newick::annotated_newick;
+pub use prior::{BranchingPenaltyPrior, TreePrior, UniformPrior};
+pub use summary::topology_frequencies;
+pub use validation::{tree_covers_mutations, validate_mutation_tree};
@@ -0,0 +1,51 @@
+/// Export of inferred trees to annotated formats.
+use crate::tree::{Node, Tree};
+use std::collections::HashMap;
+
+/// Writes the tree in the Newick format with support values
+/// stored as NHX annotations, as read by ETE (`Tree(newick, format=1)`) and iTOL.
+///
+/// All the nodes, including the internal ones, keep their labels, exactly as in
+/// `Tree::to_newick`, so that the output can be mapped back to the tree.
+/// The label of every internal node (i.e., a node with children) contained in `support`
+/// is followed by `[&&NHX:support=<value>]`, e.g., `(3)2[&&NHX:support=0.87]`.
+/// Leaves, and internal nodes not contained in `support`, are written with the bare label.
+/// Children are written in ascending order.
+pub fn annotated_newick(tree: &Tree, support: &HashMap<Node, f64>) -> String {
+    tree.to_newick_with(|node| match support.get(&node) {
+        Some(value) if !tree.get_children(node).is_empty() => {
+            format!("[&&NHX:support={}]", value)
+        }
+        _ => String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_utils::simple_tree;
+
+    #[test]
+    fn simple_tree_with_support() {
+        // Node 0 (the root) and the internal node 1 have no support value,
+        // while the support of the leaf 11 is not written.
+        let support = HashMap::from([(2, 0.5), (10, 1.0), (11, 0.25)]);
+        assert_eq!(
+            annotated_newick(&simple_tree(), &support),
+            "(((3)2[&&NHX:support=0.5])1,(11)10[&&NHX:support=1])0;"
+        );
+    }
+
+    #[test]
+    fn no_support_is_plain_newick() {
+        let tree = simple_tree();
+        assert_eq!(annotated_newick(&tree, &HashMap::new()), tree.to_newick());
+    }
+
+    #[test]
+    fn single_node() {
+        let tree = Tree::new(5);
+        assert_eq!(annotated_newick(&tree, &HashMap::new()), "5;");
+        assert_eq!(annotated_newick(&tree, &HashMap::from([(5, 0.9)])), "5;");
+    }
+}
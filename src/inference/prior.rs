@@ -0,0 +1,88 @@
+/// Priors over the space of trees.
+use crate::mcmc::mh::LogProb;
+use crate::tree::Tree;
+
+/// Prior distribution over trees, given by its (possibly unnormalized)
+/// log-density. The prior must be normalizable (on a finite space of trees
+/// with fixed labels this holds for any prior returning finite values),
+/// so that the sampler targets a proper posterior.
+pub trait TreePrior {
+    fn log_prior(&self, tree: &Tree) -> LogProb;
+}
+
+/// Uniform prior over trees.
+pub struct UniformPrior;
+
+impl TreePrior for UniformPrior {
+    fn log_prior(&self, _tree: &Tree) -> LogProb {
+        0.0
+    }
+}
+
+/// Prior penalizing trees with a high mean branching factor, i.e.,
+/// `log p(tree) = -penalty * (mean number of children of internal nodes)`.
+/// For a single-node tree the branching factor is taken to be 0.
+pub struct BranchingPenaltyPrior {
+    pub penalty: LogProb,
+}
+
+impl BranchingPenaltyPrior {
+    pub fn new(penalty: LogProb) -> Self {
+        BranchingPenaltyPrior { penalty }
+    }
+}
+
+impl TreePrior for BranchingPenaltyPrior {
+    fn log_prior(&self, tree: &Tree) -> LogProb {
+        let n_internal = tree
+            .get_nodes()
+            .into_iter()
+            .filter(|&node| !tree.get_children(node).is_empty())
+            .count();
+        if n_internal == 0 {
+            return 0.0;
+        }
+        // Every node apart from the root is a child of an internal node.
+        let mean_branching = (tree.len() - 1) as LogProb / n_internal as LogProb;
+        -self.penalty * mean_branching
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{create_chain_tree, create_star_tree};
+
+    #[test]
+    fn uniform_prior() {
+        let tree = create_star_tree(0, [1, 2, 3]).unwrap();
+        assert_eq!(UniformPrior.log_prior(&tree), 0.0);
+    }
+
+    #[test]
+    fn branching_penalty_values() {
+        let prior = BranchingPenaltyPrior::new(2.0);
+
+        let chain = create_chain_tree([0, 1, 2, 3]).unwrap();
+        assert_eq!(prior.log_prior(&chain), -2.0);
+
+        let star = create_star_tree(0, [1, 2, 3]).unwrap();
+        assert_eq!(prior.log_prior(&star), -6.0);
+
+        assert_eq!(prior.log_prior(&Tree::new(0)), 0.0);
+    }
+
+    #[test]
+    fn branching_penalty_prefers_chains() {
+        let chain = create_chain_tree([0, 1, 2, 3]).unwrap();
+        let star = create_star_tree(0, [1, 2, 3]).unwrap();
+
+        let weak = BranchingPenaltyPrior::new(0.1);
+        let strong = BranchingPenaltyPrior::new(10.0);
+
+        let weak_gap = weak.log_prior(&chain) - weak.log_prior(&star);
+        let strong_gap = strong.log_prior(&chain) - strong.log_prior(&star);
+        assert!(weak_gap > 0.0);
+        assert!(strong_gap > weak_gap);
+    }
+}
@@ -0,0 +1,55 @@
+/// Summaries of the posterior distribution over trees.
+use crate::smc::ParticleSet;
+use crate::tree::Tree;
+use std::collections::HashMap;
+
+/// Returns the posterior probabilities of distinct trees (topologies together
+/// with labels) represented by the particles, i.e., pairs
+/// `(canonical_string, probability)`, obtained by summing
+/// the normalized weights of the particles with the same canonical string.
+/// The pairs are sorted by descending probability (ties by the string).
+pub fn topology_frequencies(particles: &ParticleSet<Tree>) -> Vec<(String, f64)> {
+    let mut frequencies: HashMap<String, f64> = HashMap::new();
+    for (tree, weight) in particles
+        .states()
+        .iter()
+        .zip(particles.normalized_weights())
+    {
+        *frequencies.entry(tree.canonical_string()).or_default() += weight;
+    }
+
+    let mut frequencies: Vec<(String, f64)> = frequencies.into_iter().collect();
+    frequencies.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    frequencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcmc::mh::LogProb;
+    use crate::tree::{create_chain_tree, create_star_tree};
+
+    #[test]
+    fn two_topologies() {
+        let chain = create_chain_tree([0, 1, 2]).unwrap();
+        let star = create_star_tree(0, [1, 2]).unwrap();
+
+        // Weights proportional to 1, 2, 3, 4.
+        let log_weights = [1.0 as LogProb, 2.0, 3.0, 4.0]
+            .iter()
+            .map(|w| w.ln())
+            .collect();
+        let particles = ParticleSet::new(
+            vec![chain.clone(), star.clone(), chain.clone(), star.clone()],
+            log_weights,
+        );
+
+        let frequencies = topology_frequencies(&particles);
+        assert_eq!(frequencies.len(), 2);
+
+        assert_eq!(frequencies[0].0, star.canonical_string());
+        assert!((frequencies[0].1 - 0.6).abs() < 1e-6);
+        assert_eq!(frequencies[1].0, chain.canonical_string());
+        assert!((frequencies[1].1 - 0.4).abs() < 1e-6);
+    }
+}
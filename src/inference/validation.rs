@@ -0,0 +1,133 @@
+/// Checks of the preconditions used by the inference procedures.
+use crate::tree::{Node, Tree, TreeError};
+
+/// Checks whether the non-root nodes of `tree` are exactly the mutations
+/// `0, 1, ..., n_mutations - 1`. The label of the root is arbitrary
+/// and it does not count as a mutation.
+///
+/// Returns `NodeNotFound` if some mutation is not a non-root node of the tree
+/// and `TopologyError` if the tree contains a non-root node which is not a mutation.
+pub fn tree_covers_mutations(tree: &Tree, n_mutations: usize) -> Result<(), TreeError> {
+    let root = tree.get_root();
+    for mutation in 0..n_mutations {
+        let node = mutation as Node;
+        if node == root || !tree.contains(node) {
+            return Err(TreeError::NodeNotFound);
+        }
+    }
+
+    // All the mutations are present, so any additional node is an extra label.
+    if tree.len() != n_mutations + 1 {
+        return Err(TreeError::TopologyError);
+    }
+    Ok(())
+}
+
+/// Checks whether `tree` is a mutation tree in the SCITE convention:
+/// the root is the germline node `germline` (carrying no mutation),
+/// the remaining nodes are exactly the mutations `0, 1, ..., n_mutations - 1`
+/// and the structure is valid.
+///
+/// Returns `TopologyError` if the root is not `germline` or the structure is invalid,
+/// and see `tree_covers_mutations` for the errors related to the labels.
+pub fn validate_mutation_tree(
+    tree: &Tree,
+    n_mutations: usize,
+    germline: Node,
+) -> Result<(), TreeError> {
+    if tree.get_root() != germline {
+        return Err(TreeError::TopologyError);
+    }
+    tree_covers_mutations(tree, n_mutations)?;
+    if !tree.is_valid() {
+        return Err(TreeError::TopologyError);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::create_chain_tree;
+
+    mod test_validate_mutation_tree {
+        use super::*;
+
+        #[test]
+        fn conformant_tree() {
+            let mut tree = create_chain_tree([100, 0, 1]).unwrap();
+            tree.add_node(100, 2).unwrap();
+            assert!(validate_mutation_tree(&tree, 3, 100).is_ok());
+            assert!(validate_mutation_tree(&Tree::new(100), 0, 100).is_ok());
+        }
+
+        #[test]
+        fn wrong_germline() {
+            let tree = create_chain_tree([100, 0, 1, 2]).unwrap();
+            assert!(matches!(
+                validate_mutation_tree(&tree, 3, 50),
+                Err(TreeError::TopologyError)
+            ));
+        }
+
+        #[test]
+        fn germline_is_a_mutation() {
+            let tree = create_chain_tree([0, 1, 2]).unwrap();
+            assert!(matches!(
+                validate_mutation_tree(&tree, 3, 0),
+                Err(TreeError::NodeNotFound)
+            ));
+        }
+
+        #[test]
+        fn missing_mutation() {
+            let tree = create_chain_tree([100, 0, 2]).unwrap();
+            assert!(matches!(
+                validate_mutation_tree(&tree, 3, 100),
+                Err(TreeError::NodeNotFound)
+            ));
+        }
+
+        #[test]
+        fn extra_node() {
+            let tree = create_chain_tree([100, 0, 1, 2, 3]).unwrap();
+            assert!(matches!(
+                validate_mutation_tree(&tree, 3, 100),
+                Err(TreeError::TopologyError)
+            ));
+        }
+    }
+
+    #[test]
+    fn correct_cover() {
+        let tree = create_chain_tree([100, 0, 1, 2]).unwrap();
+        assert!(tree_covers_mutations(&tree, 3).is_ok());
+    }
+
+    #[test]
+    fn missing_mutation() {
+        let tree = create_chain_tree([100, 0, 2]).unwrap();
+        assert!(matches!(
+            tree_covers_mutations(&tree, 3),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn mutation_at_the_root() {
+        let tree = create_chain_tree([0, 1, 2]).unwrap();
+        assert!(matches!(
+            tree_covers_mutations(&tree, 3),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn extra_label() {
+        let tree = create_chain_tree([100, 0, 1, 2, 7]).unwrap();
+        assert!(matches!(
+            tree_covers_mutations(&tree, 3),
+            Err(TreeError::TopologyError)
+        ));
+    }
+}
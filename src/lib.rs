@@ -1,2 +1,5 @@
+pub mod inference;
+pub mod likelihood;
 pub mod mcmc;
+pub mod smc;
 pub mod tree;
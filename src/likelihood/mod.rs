@@ -0,0 +1,3 @@
+mod root_paths;
+
+pub use root_paths::RootPaths;
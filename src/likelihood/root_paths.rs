@@ -0,0 +1,80 @@
+/// Precomputed ancestry relations, used to speed up the likelihood evaluation.
+use crate::tree::metrics::compact_labels;
+use crate::tree::{Node, Tree};
+use std::collections::HashMap;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// For each node stores the set of its ancestors (including the node itself)
+/// as a bitset over compacted node indices (see `compact_labels`),
+/// so that ancestry can be checked with a single bit test,
+/// rather than by walking towards the root.
+pub struct RootPaths {
+    index: HashMap<Node, usize>,
+    paths: Vec<Vec<u64>>,
+}
+
+impl RootPaths {
+    pub fn from_tree(tree: &Tree) -> Self {
+        let index = compact_labels(tree);
+        let n_words = index.len().div_ceil(WORD_BITS);
+
+        // Nodes are visited from the root, so the path of the parent is already known.
+        let mut paths = vec![vec![0; n_words]; index.len()];
+        for node in tree.preorder() {
+            let i = index[&node];
+            if let Some(parent) = tree.get_parent(node) {
+                paths[i] = paths[index[&parent]].clone();
+            }
+            paths[i][i / WORD_BITS] |= 1 << (i % WORD_BITS);
+        }
+
+        RootPaths { index, paths }
+    }
+
+    /// Checks if `a` is an ancestor of `b` or `a == b`.
+    /// Returns false if either node is not in the tree.
+    pub fn is_ancestor_or_self(&self, a: Node, b: Node) -> bool {
+        match (self.index.get(&a), self.index.get(&b)) {
+            (Some(&i), Some(&j)) => self.paths[j][i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Generates a tree with 100 nodes, in which each node `k > 0`
+    /// is attached to a deterministic, but irregular, choice among the nodes `0..k`.
+    fn moderate_tree() -> Tree {
+        let mut tree = Tree::new(0);
+        for node in 1..100 {
+            let parent = (node * 7919) % 101 % node;
+            tree.add_node(parent, node).unwrap();
+        }
+        tree
+    }
+
+    #[test]
+    fn agrees_with_is_ancestor() {
+        let tree = moderate_tree();
+        let paths = RootPaths::from_tree(&tree);
+
+        for a in tree.get_nodes() {
+            for b in tree.get_nodes() {
+                let expected = a == b || tree.is_ancestor(a, b);
+                assert_eq!(paths.is_ancestor_or_self(a, b), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_nodes() {
+        let tree = moderate_tree();
+        let paths = RootPaths::from_tree(&tree);
+        assert!(!paths.is_ancestor_or_self(0, 1000));
+        assert!(!paths.is_ancestor_or_self(1000, 5));
+    }
+}
@@ -0,0 +1,74 @@
+/// Convergence diagnostics for MCMC traces.
+use std::iter::zip;
+
+/// Returns the normalized autocorrelations of `trace` at lags `0..=max_lag`.
+///
+/// The autocorrelation at lag `k` is estimated as
+///   `sum_t (x_t - m) (x_{t+k} - m) / sum_t (x_t - m)^2`,
+/// where `m` is the mean of the trace, so that the value at lag 0 is 1.0.
+///
+/// Lags larger than `trace.len() - 1` are dropped, so the returned vector
+/// has `min(max_lag + 1, trace.len())` entries.
+/// For a constant trace the autocorrelations at positive lags are undefined and set to NaN.
+pub fn autocorrelation(trace: &[f64], max_lag: usize) -> Vec<f64> {
+    let n = trace.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mean = trace.iter().sum::<f64>() / n as f64;
+    let centered: Vec<f64> = trace.iter().map(|x| x - mean).collect();
+    let sum_squares: f64 = centered.iter().map(|x| x * x).sum();
+
+    let mut acf = Vec::with_capacity(max_lag.min(n - 1) + 1);
+    acf.push(1.0);
+    for lag in 1..=max_lag.min(n - 1) {
+        let cov: f64 = zip(&centered[..n - lag], &centered[lag..])
+            .map(|(a, b)| a * b)
+            .sum();
+        acf.push(if sum_squares > 0.0 {
+            cov / sum_squares
+        } else {
+            f64::NAN
+        });
+    }
+    acf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_distr::{Distribution, StandardNormal};
+
+    #[test]
+    fn test_ar1_decays_geometrically() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        let phi = 0.7;
+
+        let mut trace = Vec::with_capacity(100_000);
+        let mut x = 0.0;
+        for _ in 0..100_000 {
+            let eps: f64 = StandardNormal.sample(&mut rng);
+            x = phi * x + eps;
+            trace.push(x);
+        }
+
+        let acf = autocorrelation(&trace, 5);
+        assert_eq!(acf.len(), 6);
+        assert_eq!(acf[0], 1.0);
+        for (lag, value) in acf.iter().enumerate() {
+            assert!((value - phi.powi(lag as i32)).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_short_and_degenerate_traces() {
+        assert!(autocorrelation(&[], 3).is_empty());
+        assert_eq!(autocorrelation(&[1.0, 2.0], 10).len(), 2);
+
+        let acf = autocorrelation(&[3.0; 4], 2);
+        assert_eq!(acf[0], 1.0);
+        assert!(acf[1..].iter().all(|x| x.is_nan()));
+    }
+}
@@ -9,7 +9,7 @@ pub fn metropolis_ratio(
     log2given1: LogProb,
 ) -> LogProb {
     let a = logp2 + log1given2 - (logp1 + log2given1);
-    return LogProb::min(1.0, a.exp());
+    LogProb::min(1.0, a.exp())
 }
 
 /// Performs a Metropolis–Hastings step.
@@ -22,16 +22,8 @@ pub fn metropolis_hastings_step<S>(
     log1given2: LogProb,
     log2given1: LogProb,
 ) -> S {
-    let a = metropolis_ratio(logp1, logp2, log1given2, log2given1);
-
-    let uniform = Uniform::<LogProb>::new(0.0, 1.0);
-    let u: LogProb = uniform.sample(rng);
-
-    if u < a {
-        return state2;
-    } else {
-        return state1;
-    }
+    let log_accept = logp2 + log1given2 - (logp1 + log2given1);
+    metropolis_step_custom(rng, state1, state2, log_accept).0
 }
 
 /// Performs a Metropolis step, i.e., assumes that `q(1|2) = q(2|1)`.
@@ -42,16 +34,57 @@ pub fn metropolis_symmetric_step<S>(
     logp1: LogProb,
     logp2: LogProb,
 ) -> S {
-    return metropolis_hastings_step(rng, state1, state2, logp1, logp2, 0.0, 0.0);
+    metropolis_hastings_step(rng, state1, state2, logp1, logp2, 0.0, 0.0)
+}
+
+/// Performs an accept/reject step with a precomputed log-acceptance probability
+/// `log_accept` (values above 0 are treated as 0, i.e., the move is always accepted).
+/// This allows constructing the acceptance probability in a custom way
+/// (e.g., for tempering or moves with priors).
+/// Returns the new state and whether the move was accepted.
+pub fn metropolis_step_custom<S>(
+    rng: &mut impl rand::Rng,
+    state1: S,
+    state2: S,
+    log_accept: LogProb,
+) -> (S, bool) {
+    let uniform = Uniform::<LogProb>::new(0.0, 1.0);
+    let u: LogProb = uniform.sample(rng);
+
+    if u < log_accept.exp() {
+        (state2, true)
+    } else {
+        (state1, false)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use rand::SeedableRng;
 
     #[test]
     fn test_add() {
         assert_eq!(1 + 2, 3);
     }
+
+    #[test]
+    fn test_custom_step_always_accepts() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..1000 {
+            assert_eq!(metropolis_step_custom(&mut rng, 1, 2, 0.0), (2, true));
+        }
+    }
+
+    #[test]
+    fn test_custom_step_never_accepts() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..1000 {
+            assert_eq!(
+                metropolis_step_custom(&mut rng, 1, 2, LogProb::NEG_INFINITY),
+                (1, false)
+            );
+        }
+    }
 }
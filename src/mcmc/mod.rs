@@ -1 +1,4 @@
+pub mod diagnostics;
+pub mod gibbs;
 pub mod mh;
+pub mod tree_moves;
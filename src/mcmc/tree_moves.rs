@@ -0,0 +1,156 @@
+/// Proposals (moves) on the space of trees.
+use super::mh::LogProb;
+use crate::tree::{Node, Tree};
+use rand::seq::SliceRandom;
+
+/// Labels adjacent in value to `node` (i.e., `node - 1` and `node + 1`)
+/// which are not used in the tree.
+fn free_adjacent_labels(tree: &Tree, node: Node) -> Vec<Node> {
+    [node.checked_sub(1), node.checked_add(1)]
+        .into_iter()
+        .flatten()
+        .filter(|&label| !tree.contains(label))
+        .collect()
+}
+
+/// Proposes a new tree by changing the label of a single node
+/// to a value adjacent to it (i.e., by one), provided the new label is free.
+///
+/// This move is intended for models in which the labels encode
+/// ordered quantities (e.g., ranks of mutation timings)
+/// and it should be combined with topology-changing moves.
+///
+/// A node is chosen uniformly at random and then one of the free adjacent labels
+/// is chosen uniformly at random. If the chosen node does not have any free adjacent
+/// labels, the tree is left unchanged.
+///
+/// Returns the proposed tree together with the log-probabilities
+/// `log q(proposed | tree)` and `log q(tree | proposed)`.
+pub fn propose_label_nudge(tree: &Tree, rng: &mut impl rand::Rng) -> (Tree, LogProb, LogProb) {
+    let nodes = tree.get_nodes();
+    let node = *nodes.choose(rng).unwrap();
+
+    let nudges = free_adjacent_labels(tree, node);
+    let new_label = match nudges.choose(rng) {
+        Some(&label) => label,
+        None => return (tree.clone(), 0.0, 0.0),
+    };
+
+    let mut proposed = tree.clone();
+    proposed.unsafe_replace_label(node, new_label);
+
+    // The reverse move has to choose the same node (now labeled `new_label`)
+    // and nudge it back. Note that `node` is now free.
+    let n_reverse = free_adjacent_labels(&proposed, new_label).len();
+    let log_n = (nodes.len() as LogProb).ln();
+
+    let log_forward = -log_n - (nudges.len() as LogProb).ln();
+    let log_backward = -log_n - (n_reverse as LogProb).ln();
+    (proposed, log_forward, log_backward)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::create_chain_tree;
+    use rand::SeedableRng;
+
+    mod test_propose_label_nudge {
+        use super::*;
+
+        fn tree() -> Tree {
+            let mut tree = create_chain_tree([0, 1, 5]).unwrap();
+            tree.add_node(0, 10).unwrap();
+            tree
+        }
+
+        #[test]
+        fn topology_is_fixed() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+            let tree = tree();
+
+            for _ in 0..50 {
+                let (proposed, log_forward, log_backward) = propose_label_nudge(&tree, &mut rng);
+                // Node 0 cannot be nudged, so the tree is left unchanged.
+                if proposed == tree {
+                    assert_eq!((log_forward, log_backward), (0.0, 0.0));
+                    continue;
+                }
+
+                let old: Vec<Node> = tree
+                    .get_nodes()
+                    .into_iter()
+                    .filter(|&node| !proposed.contains(node))
+                    .collect();
+                let new: Vec<Node> = proposed
+                    .get_nodes()
+                    .into_iter()
+                    .filter(|&node| !tree.contains(node))
+                    .collect();
+                assert_eq!(old.len(), 1);
+                assert_eq!(new.len(), 1);
+                assert_eq!(old[0].abs_diff(new[0]), 1);
+
+                // Changing the label back recovers the original tree.
+                let mut recovered = proposed.clone();
+                recovered.unsafe_replace_label(new[0], old[0]);
+                assert_eq!(recovered, tree);
+                assert!(proposed.is_valid());
+            }
+        }
+
+        #[test]
+        fn proposal_probabilities() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+            let tree = tree();
+            let ln = |x: LogProb| x.ln();
+            let mut seen_asymmetric = false;
+
+            for _ in 0..100 {
+                let (proposed, log_forward, log_backward) = propose_label_nudge(&tree, &mut rng);
+                if proposed == tree {
+                    continue;
+                }
+
+                let old = tree
+                    .get_nodes()
+                    .into_iter()
+                    .find(|&node| !proposed.contains(node))
+                    .unwrap();
+                let new = proposed
+                    .get_nodes()
+                    .into_iter()
+                    .find(|&node| !tree.contains(node))
+                    .unwrap();
+
+                // There are 4 nodes. Node 1 has a single free neighbour (2),
+                // while 5, 10 and every nudged node have two.
+                let (expected_forward, expected_backward) = match (old, new) {
+                    (1, 2) => {
+                        seen_asymmetric = true;
+                        (-ln(4.0) - ln(1.0), -ln(4.0) - ln(2.0))
+                    }
+                    (5, 4) | (5, 6) | (10, 9) | (10, 11) => {
+                        (-ln(4.0) - ln(2.0), -ln(4.0) - ln(2.0))
+                    }
+                    _ => panic!("Unexpected move {} -> {}", old, new),
+                };
+                assert!((log_forward - expected_forward).abs() < 1e-6);
+                assert!((log_backward - expected_backward).abs() < 1e-6);
+            }
+            assert!(seen_asymmetric);
+        }
+
+        #[test]
+        fn nudging_1_to_2() {
+            // Node 1 in the tree 0–1–5 can only be nudged to 2,
+            // while from 2 both 1 and 3 are free.
+            let tree = create_chain_tree([0, 1, 5]).unwrap();
+            assert_eq!(free_adjacent_labels(&tree, 1), vec![2]);
+
+            let mut proposed = tree.clone();
+            proposed.unsafe_replace_label(1, 2);
+            assert_eq!(free_adjacent_labels(&proposed, 2), vec![1, 3]);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+mod particles;
+pub mod resampling;
+
+pub use particles::ParticleSet;
@@ -0,0 +1,132 @@
+use crate::mcmc::mh::LogProb;
+
+/// Weighted set of particles, i.e., states with (unnormalized) log-weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleSet<S> {
+    states: Vec<S>,
+    log_weights: Vec<LogProb>,
+}
+
+impl<S> ParticleSet<S> {
+    /// Creates a new particle set. Panics if the lengths do not agree.
+    pub fn new(states: Vec<S>, log_weights: Vec<LogProb>) -> Self {
+        assert_eq!(
+            states.len(),
+            log_weights.len(),
+            "Each particle has to have a weight."
+        );
+        ParticleSet {
+            states,
+            log_weights,
+        }
+    }
+
+    /// Returns the number of particles.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn log_weights(&self) -> &[LogProb] {
+        &self.log_weights
+    }
+
+    /// Returns the weights normalized to sum up to 1
+    /// (computed in a numerically stable way from the log-weights).
+    pub fn normalized_weights(&self) -> Vec<f64> {
+        let max = self
+            .log_weights
+            .iter()
+            .map(|&w| w as f64)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = self
+            .log_weights
+            .iter()
+            .map(|&w| (w as f64 - max).exp())
+            .collect();
+        let total: f64 = weights.iter().sum();
+        weights.into_iter().map(|w| w / total).collect()
+    }
+
+    /// Estimates the expectation of `f`, i.e., `sum_i w_i * f(particle_i)`
+    /// using the normalized weights. For an empty set returns NaN.
+    pub fn weighted_mean<F: Fn(&S) -> f64>(&self, f: F) -> f64 {
+        if self.is_empty() {
+            return f64::NAN;
+        }
+        self.normalized_weights()
+            .iter()
+            .zip(self.states.iter())
+            .map(|(w, state)| w * f(state))
+            .sum()
+    }
+
+    /// Applies `f` to each particle, keeping the weights.
+    pub fn map_states<T, F: Fn(&S) -> T>(&self, f: F) -> ParticleSet<T> {
+        ParticleSet {
+            states: self.states.iter().map(f).collect(),
+            log_weights: self.log_weights.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_states() {
+        let particles = ParticleSet::new(vec![1u32, 2, 5], vec![-1.0, 0.0, -3.5]);
+        let mapped = particles.map_states(|x| x * 2);
+
+        assert_eq!(mapped.states(), &[2, 4, 10]);
+        assert_eq!(mapped.log_weights(), particles.log_weights());
+    }
+
+    #[test]
+    fn test_map_states_changes_type() {
+        let particles = ParticleSet::new(vec![1u32, 2], vec![0.5, 0.5]);
+        let mapped = particles.map_states(|x| x.to_string());
+        assert_eq!(mapped.states(), &["1".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    fn test_normalized_weights() {
+        let particles = ParticleSet::new(vec![0, 1], vec![-100.0, -100.0 + (3.0 as LogProb).ln()]);
+        let weights = particles.normalized_weights();
+        assert!((weights[0] - 0.25).abs() < 1e-5);
+        assert!((weights[1] - 0.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_weighted_mean() {
+        // Weights proportional to 1, 2, 5.
+        let log_weights = [1.0 as LogProb, 2.0, 5.0].iter().map(|w| w.ln()).collect();
+        let particles = ParticleSet::new(vec![4.0, -1.0, 2.0], log_weights);
+
+        let mean = particles.weighted_mean(|&x| x);
+        assert!((mean - 1.5).abs() < 1e-6);
+
+        let second_moment = particles.weighted_mean(|&x| x * x);
+        assert!((second_moment - 4.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weighted_mean_empty() {
+        let particles: ParticleSet<f64> = ParticleSet::new(vec![], vec![]);
+        assert!(particles.weighted_mean(|&x| x).is_nan());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lengths_must_agree() {
+        ParticleSet::new(vec![1u32, 2], vec![0.0]);
+    }
+}
@@ -0,0 +1,125 @@
+use rand::distributions::WeightedIndex;
+use rand_distr::Distribution;
+
+/// Residual resampling.
+///
+/// Given weights `w_1, ..., w_n` (non-negative, not necessarily normalized)
+/// returns `n` indices of the selected particles, sorted in ascending order.
+///
+/// The algorithm works in two stages:
+///   1. Each index `i` is copied deterministically `floor(n * w_i)` times
+///      (where the weights have been normalized).
+///   2. The remaining `r = n - sum_i floor(n * w_i)` indices are drawn
+///      by multinomial resampling with probabilities proportional to the
+///      residual weights `n * w_i - floor(n * w_i)`.
+///
+/// As only the residual part is random, this scheme has lower variance
+/// than multinomial resampling.
+///
+/// Panics if the weights are not all finite and non-negative, or if they sum to 0
+/// (unless there are no weights at all, in which case no indices are returned).
+pub fn residual_resample(weights: &[f64], rng: &mut impl rand::Rng) -> Vec<usize> {
+    let n = weights.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let total: f64 = weights.iter().sum();
+    assert!(
+        weights.iter().all(|w| w.is_finite() && *w >= 0.0) && total > 0.0 && total.is_finite(),
+        "The weights have to be finite, non-negative and not all 0."
+    );
+
+    let mut indices = Vec::with_capacity(n);
+    let mut residuals = Vec::with_capacity(n);
+
+    // Deterministic copies.
+    for (i, &w) in weights.iter().enumerate() {
+        let expected = n as f64 * w / total;
+        let copies = expected.floor();
+        for _ in 0..(copies as usize) {
+            indices.push(i);
+        }
+        residuals.push(expected - copies);
+    }
+
+    // Multinomial resampling on the residual weights.
+    let remainder = n - indices.len();
+    if remainder > 0 {
+        let dist = WeightedIndex::new(&residuals).unwrap();
+        for _ in 0..remainder {
+            indices.push(dist.sample(rng));
+        }
+        indices.sort();
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn counts(indices: &[usize], n: usize) -> Vec<usize> {
+        let mut counts = vec![0; n];
+        for &i in indices {
+            counts[i] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn deterministic_copies_match_floor_counts() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        let weights = [0.0625, 0.3125, 0.125, 0.5];
+
+        for _ in 0..100 {
+            let indices = residual_resample(&weights, &mut rng);
+            assert_eq!(indices.len(), weights.len());
+
+            let counts = counts(&indices, weights.len());
+            // Floors are [0, 1, 0, 2] and one index is drawn from the residuals.
+            let floors = [0, 1, 0, 2];
+            for (count, floor) in counts.iter().zip(floors) {
+                assert!(*count >= floor);
+                assert!(*count <= floor + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_weights_are_deterministic() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        // Unnormalized weights, proportional to [0.25, 0.0, 0.5, 0.25].
+        let weights = [1.0, 0.0, 2.0, 1.0];
+
+        let indices = residual_resample(&weights, &mut rng);
+        assert_eq!(indices, vec![0, 2, 2, 3]);
+    }
+
+    #[test]
+    fn no_weights() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        assert!(residual_resample(&[], &mut rng).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "not all 0")]
+    fn all_zero_weights() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        residual_resample(&[0.0, 0.0, 0.0], &mut rng);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-negative")]
+    fn negative_weight() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        residual_resample(&[1.0, -0.5, 1.0], &mut rng);
+    }
+
+    #[test]
+    #[should_panic(expected = "finite")]
+    fn nan_weight() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        residual_resample(&[1.0, f64::NAN], &mut rng);
+    }
+}
@@ -1,6 +1,10 @@
 /// Implementation of a tree together with its core utilities.
+use rand::seq::SliceRandom;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
 
 /// Alias for the node label type.
 /// Note that it's a small type implementing Copy
@@ -8,7 +12,8 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 pub type Node = u32;
 
 /// Tree data structure.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Cloning creates a deep copy, i.e., mutating a clone never affects the original.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Tree {
     root: Node,
     nodes: HashSet<Node>,
@@ -28,15 +33,32 @@ impl PartialEq for Tree {
 
 impl Eq for Tree {}
 
+/// Prints the root, the sorted edges and the sorted nodes,
+/// so that the output does not depend on the `HashMap` ordering
+/// (and failing equality assertions are easy to read).
+impl fmt::Debug for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tree")
+            .field("root", &self.root)
+            .field("edges", &self.edges())
+            .field("nodes", &self.get_nodes())
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub enum TreeError {
     NodeNotFound,
     NodeAlreadyExists,
     TopologyError,
+    /// All the possible labels are already used, so a fresh one cannot be allocated.
+    LabelSpaceExhausted,
 }
 
 impl Tree {
     /// Calculates the number of nodes in the tree
+    // A tree always contains its root, so it is never empty.
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         self.nodes.len()
     }
@@ -47,8 +69,8 @@ impl Tree {
         nodes.insert(root);
 
         Tree {
-            root: root,
-            nodes: nodes,
+            root,
+            nodes,
             children: HashMap::new(),
             parents: HashMap::new(),
         }
@@ -64,20 +86,93 @@ impl Tree {
         self.nodes.contains(&node)
     }
 
+    /// Returns the nodes of the tree, sorted in ascending order.
+    pub fn get_nodes(&self) -> Vec<Node> {
+        let mut nodes: Vec<Node> = self.nodes.iter().copied().collect();
+        nodes.sort();
+        nodes
+    }
+
+    /// Returns the leaves of the tree, sorted in ascending order.
+    /// For the single-node tree the root is the only leaf.
+    pub fn get_leaves(&self) -> Vec<Node> {
+        let mut leaves: Vec<Node> = self
+            .nodes
+            .iter()
+            .copied()
+            .filter(|node| !self.children.contains_key(node))
+            .collect();
+        leaves.sort();
+        leaves
+    }
+
+    /// Returns the number of leaves.
+    pub fn count_leaves(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| !self.children.contains_key(node))
+            .count()
+    }
+
+    /// Returns the number of cherries, i.e., internal nodes with exactly two children,
+    /// both of which are leaves.
+    /// Nodes with more than two leaf children, or with a non-leaf child, are not counted.
+    pub fn num_cherries(&self) -> usize {
+        self.children
+            .values()
+            .filter(|children| {
+                children.len() == 2
+                    && children
+                        .iter()
+                        .all(|child| !self.children.contains_key(child))
+            })
+            .count()
+    }
+
+    /// Returns the nodes in pre-order, so that each node appears before its descendants.
+    /// The children of each node are visited in ascending order.
+    pub fn preorder(&self) -> Vec<Node> {
+        let mut order = Vec::with_capacity(self.len());
+        let mut stack = vec![self.root];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            stack.extend(self.get_children(node).into_iter().rev());
+        }
+        order
+    }
+
+    /// Returns the number of leaves in the subtree of each node
+    /// (a leaf counts itself, so its count is 1), computed in a single post-order pass.
+    pub fn leaf_counts(&self) -> HashMap<Node, usize> {
+        let order = self.preorder();
+
+        // In reverse pre-order all children are visited before their parent.
+        let mut counts: HashMap<Node, usize> = HashMap::with_capacity(order.len());
+        for &node in order.iter().rev() {
+            let count = match self.children.get(&node) {
+                Some(children) => children.iter().map(|child| counts[child]).sum(),
+                None => 1,
+            };
+            counts.insert(node, count);
+        }
+        counts
+    }
+
     /// See `add_node`. This method does not do checks.
     fn unsafe_add_node(&mut self, parent: Node, child: Node) {
         self.nodes.insert(child);
 
-        self.children
-            .entry(parent)
-            .or_insert_with(HashSet::new)
-            .insert(child);
+        self.children.entry(parent).or_default().insert(child);
 
         self.parents.insert(child, parent);
     }
 
     /// Adds a child node to a parent.
     pub fn add_node(&mut self, parent: Node, child: Node) -> Result<(), TreeError> {
+        // A node cannot be its own child -> Error
+        if parent == child {
+            return Err(TreeError::TopologyError);
+        }
         // Parent does not exist -> Error
         if !self.contains(parent) {
             return Err(TreeError::NodeNotFound);
@@ -91,6 +186,13 @@ impl Tree {
         Ok(())
     }
 
+    /// Adds a child node to a parent (see `add_node`) and returns the child,
+    /// so that it can be used in the next call.
+    pub fn add_node_chained(&mut self, parent: Node, child: Node) -> Result<Node, TreeError> {
+        self.add_node(parent, child)?;
+        Ok(child)
+    }
+
     /// Prints out the tree to the standard output.
     pub fn print(&self) {
         println!("{}", self.root);
@@ -169,10 +271,50 @@ impl Tree {
         true
     }
 
+    /// Removes the entries of the children map which hold an empty set
+    /// and returns how many were removed.
+    ///
+    /// All the methods of `Tree` remove the entry of a node once its last child is gone,
+    /// so that leaves have no entry at all. This restores that invariant
+    /// (on which, e.g., `PartialEq` and `get_leaves` rely) without changing the topology.
+    pub fn normalize_children_map(&mut self) -> usize {
+        let before = self.children.len();
+        self.children.retain(|_, children| !children.is_empty());
+        before - self.children.len()
+    }
+
     pub fn get_parent(&self, node: Node) -> Option<Node> {
         self.parents.get(&node).copied()
     }
 
+    /// Returns the children of `node`, sorted in ascending order.
+    /// The vector is empty if `node` is a leaf (or is not in the tree).
+    pub fn get_children(&self, node: Node) -> Vec<Node> {
+        let mut children: Vec<Node> = self
+            .children
+            .get(&node)
+            .map(|set| set.iter().copied().collect())
+            .unwrap_or_default();
+        children.sort();
+        children
+    }
+
+    /// Returns the children of `node` sorted by `key`.
+    /// Ties are broken by the label (in ascending order).
+    pub fn children_sorted_by<F, K>(&self, node: Node, key: F) -> Result<Vec<Node>, TreeError>
+    where
+        F: Fn(Node) -> K,
+        K: Ord,
+    {
+        if !self.contains(node) {
+            return Err(TreeError::NodeNotFound);
+        }
+        // `get_children` sorts by label and the sort is stable.
+        let mut children = self.get_children(node);
+        children.sort_by_key(|&child| key(child));
+        Ok(children)
+    }
+
     /// Checks if `child` is a child of `parent`
     pub fn is_child(&self, child: Node, parent: Node) -> bool {
         if let Some(node) = self.parents.get(&child) {
@@ -186,6 +328,103 @@ impl Tree {
         self.is_child(child, parent)
     }
 
+    /// Renames `old` to `new`, leaving the topology unchanged.
+    /// This method does not do checks: it assumes that `old` is in the tree
+    /// and `new` is not.
+    pub(crate) fn unsafe_replace_label(&mut self, old: Node, new: Node) {
+        self.nodes.remove(&old);
+        self.nodes.insert(new);
+
+        if self.root == old {
+            self.root = new;
+        }
+
+        if let Some(children) = self.children.remove(&old) {
+            for &child in children.iter() {
+                self.parents.insert(child, new);
+            }
+            self.children.insert(new, children);
+        }
+
+        if let Some(parent) = self.parents.remove(&old) {
+            self.parents.insert(new, parent);
+            if let Some(siblings) = self.children.get_mut(&parent) {
+                siblings.remove(&old);
+                siblings.insert(new);
+            }
+        }
+    }
+
+    /// Checks if `ancestor` is a (strict) ancestor of `node`,
+    /// by walking from `node` towards the root.
+    pub fn is_ancestor(&self, ancestor: Node, node: Node) -> bool {
+        let mut current = self.get_parent(node);
+        while let Some(parent) = current {
+            if parent == ancestor {
+                return true;
+            }
+            current = self.get_parent(parent);
+        }
+        false
+    }
+
+    /// Renames `old` to `new`, leaving the topology unchanged.
+    pub fn replace_label(&mut self, old: Node, new: Node) -> Result<(), TreeError> {
+        if !self.contains(old) {
+            return Err(TreeError::NodeNotFound);
+        }
+        if self.contains(new) {
+            return Err(TreeError::NodeAlreadyExists);
+        }
+        self.unsafe_replace_label(old, new);
+        Ok(())
+    }
+
+    /// Returns a copy of the tree with every node `n` renamed to `mapping[n]`,
+    /// leaving the topology unchanged. Nodes absent from `mapping` keep their labels.
+    ///
+    /// Returns `NodeAlreadyExists` if two nodes would receive the same label.
+    pub fn permute_labels(&self, mapping: &HashMap<Node, Node>) -> Result<Tree, TreeError> {
+        let relabel = |node: &Node| *mapping.get(node).unwrap_or(node);
+
+        let nodes: HashSet<Node> = self.nodes.iter().map(relabel).collect();
+        if nodes.len() != self.nodes.len() {
+            return Err(TreeError::NodeAlreadyExists);
+        }
+
+        Ok(Tree {
+            root: relabel(&self.root),
+            nodes,
+            children: self
+                .children
+                .iter()
+                .map(|(parent, children)| (relabel(parent), children.iter().map(relabel).collect()))
+                .collect(),
+            parents: self
+                .parents
+                .iter()
+                .map(|(child, parent)| (relabel(child), relabel(parent)))
+                .collect(),
+        })
+    }
+
+    /// Randomly permutes the labels among the nodes (e.g., to anonymize a tree),
+    /// leaving the topology unchanged.
+    ///
+    /// Returns the relabeled tree and the mapping from the new labels to the original ones,
+    /// so that `shuffled.permute_labels(&inverse)` restores the original tree.
+    pub fn shuffle_labels<R: Rng>(&self, rng: &mut R) -> (Tree, HashMap<Node, Node>) {
+        let nodes = self.get_nodes();
+        let mut shuffled = nodes.clone();
+        shuffled.shuffle(rng);
+
+        let mapping: HashMap<Node, Node> = nodes.iter().copied().zip(shuffled).collect();
+        let inverse = mapping.iter().map(|(&old, &new)| (new, old)).collect();
+        // The mapping is a permutation of the labels, so there are no collisions.
+        let tree = self.permute_labels(&mapping).unwrap();
+        (tree, inverse)
+    }
+
     /// Swaps two nodes in the tree, leaving the rest
     /// of the tree topology unchanged.
     pub fn swap_labels(&mut self, i: Node, j: Node) -> Result<(), TreeError> {
@@ -198,7 +437,9 @@ impl Tree {
             return Ok(());
         }
 
-        // If either node is the root, handle root swapping
+        // If either node is the root, handle root swapping.
+        // Note that the old root does not have an entry in `parents`,
+        // so neither of the branches below adds one for the new root.
         if self.root == i {
             self.root = j;
         } else if self.root == j {
@@ -328,6 +569,44 @@ impl Tree {
         }
     }
 
+    /// Returns the number of nodes at each depth, i.e., the entry at index `d`
+    /// is the number of nodes at depth `d` (the root is at depth 0).
+    /// The length of the vector is the height of the tree.
+    pub fn level_sizes(&self) -> Vec<usize> {
+        self.depth_profile(self.root).unwrap()
+    }
+
+    /// Returns the number of descendants of `node` at each relative depth,
+    /// i.e., the entry at index `d` is the number of nodes `d` levels below `node`
+    /// (the entry at index 0 is 1, corresponding to `node` itself).
+    pub fn depth_profile(&self, node: Node) -> Result<Vec<usize>, TreeError> {
+        if !self.contains(node) {
+            return Err(TreeError::NodeNotFound);
+        }
+
+        let mut sizes = Vec::new();
+        let mut queue = VecDeque::from([(node, 0)]);
+
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth == sizes.len() {
+                sizes.push(0);
+            }
+            sizes[depth] += 1;
+
+            if let Some(children) = self.children.get(&node) {
+                for &child in children {
+                    queue.push_back((child, depth + 1));
+                }
+            }
+        }
+        Ok(sizes)
+    }
+
+    /// Returns the width of the tree, i.e., the maximum number of nodes at a single depth.
+    pub fn width(&self) -> usize {
+        self.level_sizes().into_iter().max().unwrap()
+    }
+
     /// Returns a set (possibly empty) with descendants.
     pub fn get_descendants(&self, node: Node) -> HashSet<Node> {
         let mut descendants = HashSet::new();
@@ -358,7 +637,7 @@ impl Tree {
             return Err(TreeError::NodeAlreadyExists); // TODO: Refactor this error.
         }
         // It's not possible to reattach a node to its own subtree.
-        if self.get_descendants(node).contains(&new_parent) {
+        if self.is_ancestor(node, new_parent) {
             return Err(TreeError::TopologyError);
         }
         // At this point note that node != root, because new_parent would need
@@ -377,6 +656,388 @@ impl Tree {
 
         Ok(())
     }
+
+    /// Moves the subtree rooted at `node` one level up, so that `node` becomes
+    /// a sibling of its current parent. This is `prune_and_reattach(node, grandparent)`.
+    ///
+    /// Returns `TopologyError` if `node` is the root or a child of the root,
+    /// as then there is no grandparent.
+    pub fn move_subtree_up(&mut self, node: Node) -> Result<(), TreeError> {
+        if !self.contains(node) {
+            return Err(TreeError::NodeNotFound);
+        }
+        let grandparent = self
+            .get_parent(node)
+            .and_then(|parent| self.get_parent(parent))
+            .ok_or(TreeError::TopologyError)?;
+        self.prune_and_reattach(node, grandparent)
+    }
+
+    /// See `prune_and_reattach`. Removes the edge `parent -> child`
+    /// without doing checks, leaving `child` without a parent.
+    fn unsafe_remove_edge(&mut self, parent: Node, child: Node) {
+        if let Some(set) = self.children.get_mut(&parent) {
+            set.remove(&child);
+            if set.is_empty() {
+                self.children.remove(&parent);
+            }
+        }
+        self.parents.remove(&child);
+    }
+
+    /// Returns a label which is not used in the tree.
+    /// This is the largest label plus one or, if that overflows,
+    /// the smallest unused label.
+    fn fresh_label(&self) -> Result<Node, TreeError> {
+        if let Some(label) = self.nodes.iter().max().unwrap().checked_add(1) {
+            return Ok(label);
+        }
+        (0..=Node::MAX)
+            .find(|label| !self.contains(*label))
+            .ok_or(TreeError::LabelSpaceExhausted)
+    }
+
+    /// Reroots the tree at the edge between `outgroup` and its parent.
+    /// A new root (with a fresh label) is introduced, so that it has two children:
+    /// `outgroup` and the parent of `outgroup`, with the path from the parent
+    /// to the old root reversed.
+    pub fn reroot_at_outgroup(&mut self, outgroup: Node) -> Result<(), TreeError> {
+        if !self.contains(outgroup) {
+            return Err(TreeError::NodeNotFound);
+        }
+        // The root does not have an edge above it.
+        if outgroup == self.root {
+            return Err(TreeError::TopologyError);
+        }
+
+        let new_root = self.fresh_label()?;
+        let parent = self.parents[&outgroup];
+
+        // Collect the path from `parent` to the old root.
+        let mut path = self.path_from_root(parent);
+        path.reverse();
+
+        // Detach `outgroup` and reverse the edges along the path.
+        self.unsafe_remove_edge(parent, outgroup);
+        for pair in path.windows(2) {
+            self.unsafe_remove_edge(pair[1], pair[0]);
+        }
+        for pair in path.windows(2) {
+            self.unsafe_add_node(pair[0], pair[1]);
+        }
+
+        // Finally, attach both parts to the new root.
+        self.nodes.insert(new_root);
+        self.root = new_root;
+        self.unsafe_add_node(new_root, outgroup);
+        self.unsafe_add_node(new_root, parent);
+
+        Ok(())
+    }
+
+    /// Removes the root while it has exactly one child, promoting that child to the new root.
+    /// All the other nodes (and the edges between them) are preserved.
+    ///
+    /// Returns whether any change was made.
+    pub fn normalize_root(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(children) = self.children.get(&self.root) {
+            if children.len() != 1 {
+                break;
+            }
+            let old_root = self.root;
+            let child = *children.iter().next().unwrap();
+
+            self.unsafe_remove_edge(old_root, child);
+            self.nodes.remove(&old_root);
+            self.root = child;
+            changed = true;
+        }
+        changed
+    }
+
+    /// Returns the edges `(parent, child)` of the tree, sorted.
+    pub fn edges(&self) -> Vec<(Node, Node)> {
+        let mut edges: Vec<(Node, Node)> = self
+            .parents
+            .iter()
+            .map(|(&child, &parent)| (parent, child))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// Returns the path from the root to `node` (both inclusive).
+    /// The node has to be in the tree.
+    fn path_from_root(&self, node: Node) -> Vec<Node> {
+        let mut path: Vec<Node> = std::iter::once(node)
+            .chain(self.lineage(node).unwrap().map(|(_, parent)| parent))
+            .collect();
+        path.reverse();
+        path
+    }
+
+    /// Returns an iterator over the edges `(child, parent)` on the path
+    /// from `node` to the root (which is empty if `node` is the root).
+    pub fn lineage(
+        &self,
+        node: Node,
+    ) -> Result<impl Iterator<Item = (Node, Node)> + '_, TreeError> {
+        if !self.contains(node) {
+            return Err(TreeError::NodeNotFound);
+        }
+        let mut current = node;
+        Ok(std::iter::from_fn(move || {
+            let parent = self.get_parent(current)?;
+            let edge = (current, parent);
+            current = parent;
+            Some(edge)
+        }))
+    }
+
+    /// Folds `f` over the edges `(child, parent)` on the path from `node`
+    /// to the root (see `lineage`), starting with `init`.
+    pub fn fold_path_to_root<T, F: Fn(T, Node, Node) -> T>(
+        &self,
+        node: Node,
+        init: T,
+        f: F,
+    ) -> Result<T, TreeError> {
+        Ok(self
+            .lineage(node)?
+            .fold(init, |acc, (child, parent)| f(acc, child, parent)))
+    }
+
+    /// Returns the depth of `node`, i.e., the number of edges between
+    /// the root and `node` (the depth of the root is 0).
+    pub fn depth(&self, node: Node) -> Result<usize, TreeError> {
+        Ok(self.lineage(node)?.count())
+    }
+
+    /// Returns the lowest common ancestor of `nodes`, i.e., the deepest node
+    /// which is an ancestor of (or equal to) each of `nodes`.
+    /// Returns `TopologyError` if `nodes` is empty.
+    pub fn lca_of_set(&self, nodes: &[Node]) -> Result<Node, TreeError> {
+        if nodes.iter().any(|&node| !self.contains(node)) {
+            return Err(TreeError::NodeNotFound);
+        }
+        let (first, rest) = nodes.split_first().ok_or(TreeError::TopologyError)?;
+
+        let mut common = self.path_from_root(*first);
+        for &node in rest {
+            let path = self.path_from_root(node);
+            let shared = common
+                .iter()
+                .zip(path.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            common.truncate(shared);
+        }
+        Ok(*common.last().unwrap())
+    }
+
+    /// Returns the lowest common ancestor of `nodes` together with its depth,
+    /// i.e., the length of the lineage shared by all `nodes`.
+    /// Returns `TopologyError` if `nodes` is empty.
+    pub fn shared_lineage(&self, nodes: &[Node]) -> Result<(Node, usize), TreeError> {
+        let lca = self.lca_of_set(nodes)?;
+        Ok((lca, self.depth(lca)?))
+    }
+
+    /// Returns a 64-bit hash of the shape of the subtree rooted at `node`,
+    /// ignoring the labels and the order of children.
+    /// Hence, isomorphic subtrees have the same signature.
+    ///
+    /// The signature of a node is computed from the signatures of its children
+    /// combined with a commutative operation (wrapping sum of mixed values),
+    /// so that it does not depend on the ordering of children.
+    /// Different shapes can collide, although this is unlikely
+    /// (roughly with probability 2^-64 for a pair of random shapes), so the signature
+    /// should be used for bucketing, rather than for checking exact equality.
+    pub fn subtree_signature(&self, node: Node) -> Result<u64, TreeError> {
+        /// Finalizer of SplitMix64.
+        fn mix(mut x: u64) -> u64 {
+            x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+            x ^ (x >> 31)
+        }
+
+        fn signature(tree: &Tree, node: Node) -> u64 {
+            let mut acc: u64 = 0x9e3779b97f4a7c15;
+            if let Some(children) = tree.children.get(&node) {
+                for &child in children {
+                    acc = acc.wrapping_add(mix(signature(tree, child)));
+                }
+            }
+            mix(acc)
+        }
+
+        if self.contains(node) {
+            Ok(signature(self, node))
+        } else {
+            Err(TreeError::NodeNotFound)
+        }
+    }
+
+    /// Collapses unary chains, i.e., whenever a node has exactly one child
+    /// and this child is not a leaf, the child is contracted into its parent:
+    /// the children of the child become the children of the parent
+    /// and the child is removed. The parent's label is kept.
+    ///
+    /// Leaves are never removed, so all leaf labels are preserved
+    /// (and a node whose only child is a leaf is left unchanged).
+    ///
+    /// Returns the number of removed nodes.
+    pub fn collapse_unary_chains(&mut self) -> usize {
+        let mut removed = 0;
+        let mut stack = vec![self.root];
+
+        while let Some(parent) = stack.pop() {
+            while let Some(child) = self.only_internal_child(parent) {
+                let grandchildren = self.children.remove(&child).unwrap();
+                self.unsafe_remove_edge(parent, child);
+                self.nodes.remove(&child);
+                for grandchild in grandchildren {
+                    self.unsafe_add_node(parent, grandchild);
+                }
+                removed += 1;
+            }
+            stack.extend(self.get_children(parent));
+        }
+        removed
+    }
+
+    /// Returns the child of `node` if it is its only child and it is not a leaf.
+    fn only_internal_child(&self, node: Node) -> Option<Node> {
+        let children = self.children.get(&node)?;
+        if children.len() != 1 {
+            return None;
+        }
+        let child = *children.iter().next().unwrap();
+        if self.children.contains_key(&child) {
+            Some(child)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the first node (in breadth-first order, with children visited
+    /// in ascending order) in the subtree rooted at `start` satisfying `pred`.
+    pub fn find<F: Fn(Node) -> bool>(
+        &self,
+        start: Node,
+        pred: F,
+    ) -> Result<Option<Node>, TreeError> {
+        if !self.contains(start) {
+            return Err(TreeError::NodeNotFound);
+        }
+
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            if pred(node) {
+                return Ok(Some(node));
+            }
+            queue.extend(self.get_children(node));
+        }
+        Ok(None)
+    }
+
+    /// Returns the leaves together with their depths.
+    fn leaf_depths(&self) -> impl Iterator<Item = (Node, usize)> + '_ {
+        self.get_leaves()
+            .into_iter()
+            .map(|leaf| (leaf, self.depth(leaf).unwrap()))
+    }
+
+    /// Returns the deepest leaf and its depth.
+    /// Ties are broken by choosing the smallest label.
+    pub fn deepest_leaf(&self) -> (Node, usize) {
+        self.leaf_depths()
+            .min_by_key(|&(leaf, depth)| (Reverse(depth), leaf))
+            .unwrap()
+    }
+
+    /// Returns the shallowest leaf and its depth.
+    /// Ties are broken by choosing the smallest label.
+    pub fn shallowest_leaf(&self) -> (Node, usize) {
+        self.leaf_depths()
+            .min_by_key(|&(leaf, depth)| (depth, leaf))
+            .unwrap()
+    }
+
+    /// Returns the children of `node` ordered for a more balanced rendering:
+    /// the child with the largest subtree comes first, the one with the smallest
+    /// subtree comes last and the remaining children alternate between
+    /// large and small subtrees (ties are broken by the label).
+    ///
+    /// As the order of children does not change the tree,
+    /// this ordering is meant for presentation (layouts) only.
+    pub fn balanced_child_order(&self, node: Node) -> Result<Vec<Node>, TreeError> {
+        let by_size =
+            self.children_sorted_by(node, |child| Reverse(self.subtree_size(child).unwrap()))?;
+        if by_size.len() <= 2 {
+            return Ok(by_size);
+        }
+
+        let (largest, rest) = by_size.split_first().unwrap();
+        let (smallest, middle) = rest.split_last().unwrap();
+
+        let mut order = vec![*largest];
+        let (mut lo, mut hi) = (0, middle.len());
+        while lo < hi {
+            order.push(middle[lo]);
+            lo += 1;
+            if lo < hi {
+                hi -= 1;
+                order.push(middle[hi]);
+            }
+        }
+        order.push(*smallest);
+        Ok(order)
+    }
+
+    /// Checks if the tree is a path (chain), i.e., every node has at most one child.
+    pub fn is_path(&self) -> bool {
+        self.children.values().all(|children| children.len() <= 1)
+    }
+
+    /// Returns a leaf chosen uniformly at random.
+    /// Note that every tree has at least one leaf (for the single-node tree it is the root).
+    pub fn random_leaf<R: Rng>(&self, rng: &mut R) -> Option<Node> {
+        self.get_leaves().choose(rng).copied()
+    }
+
+    /// Returns an internal node (i.e., a node with at least one child,
+    /// which may be the root) chosen uniformly at random,
+    /// or `None` if there are no internal nodes.
+    pub fn random_internal_node<R: Rng>(&self, rng: &mut R) -> Option<Node> {
+        let mut internal: Vec<Node> = self.children.keys().copied().collect();
+        internal.sort();
+        internal.choose(rng).copied()
+    }
+
+    /// Checks if `other` is the (complete) subtree of this tree rooted at some node,
+    /// with matching labels. That is, the root of `other` is a node of this tree,
+    /// and its descendants (together with the edges between them) are exactly
+    /// the nodes (and edges) of `other`.
+    /// Returns the node at which `other` is attached, or `None`.
+    pub fn contains_subtree(&self, other: &Tree) -> Option<Node> {
+        let root = other.get_root();
+        if self.subtree_size(root).ok()? != other.len() {
+            return None;
+        }
+        // All the nodes of `other` are connected to its root by the same edges,
+        // so (as the sizes agree) the subtrees are the same.
+        let same_edges = other
+            .parents
+            .iter()
+            .all(|(&child, &parent)| self.get_parent(child) == Some(parent));
+        if same_edges {
+            Some(root)
+        } else {
+            None
+        }
+    }
 }
 
 fn _print_tree(tree: &Tree, node: Node, prefix: &str, is_last: bool) {
@@ -428,26 +1089,188 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tree::test_utils::simple_tree;
+
+    /// Applies `mutate` to `b` (which should be a clone of `a`)
+    /// and checks that `a` has not changed.
+    fn assert_independent(a: &Tree, b: &mut Tree, mutate: impl FnOnce(&mut Tree)) {
+        // The snapshot covers all the fields (including `children`)
+        // and does not rely on `Clone`.
+        let snapshot = |tree: &Tree| serde_json::to_string(tree).unwrap();
+        let before = snapshot(a);
+        mutate(b);
+        assert_eq!(snapshot(a), before);
+        assert_ne!(snapshot(b), before);
+    }
+
+    mod test_clone_independence {
+        use super::*;
+
+        #[test]
+        fn add_node() {
+            let tree = simple_tree();
+            assert_independent(&tree, &mut tree.clone(), |b| b.add_node(3, 4).unwrap());
+        }
+
+        #[test]
+        fn prune_and_reattach() {
+            let tree = simple_tree();
+            assert_independent(&tree, &mut tree.clone(), |b| {
+                b.prune_and_reattach(2, 11).unwrap()
+            });
+        }
+
+        #[test]
+        fn swap_labels() {
+            let tree = simple_tree();
+            assert_independent(&tree, &mut tree.clone(), |b| b.swap_labels(0, 3).unwrap());
+        }
+
+        #[test]
+        fn replace_label() {
+            let tree = simple_tree();
+            assert_independent(&tree, &mut tree.clone(), |b| {
+                b.replace_label(10, 20).unwrap()
+            });
+        }
+
+        #[test]
+        fn reroot_at_outgroup() {
+            let tree = simple_tree();
+            assert_independent(&tree, &mut tree.clone(), |b| {
+                b.reroot_at_outgroup(11).unwrap()
+            });
+        }
+    }
+
+    #[test]
+    fn test_get_root() {
+        let tree = simple_tree();
+        assert_eq!(tree.get_root(), 0);
+    }
+
+    #[test]
+    fn test_add_node_self_edge() {
+        let mut tree = simple_tree();
+        assert!(matches!(tree.add_node(0, 0), Err(TreeError::TopologyError)));
+        assert!(matches!(tree.add_node(3, 3), Err(TreeError::TopologyError)));
+        assert_eq!(tree, simple_tree());
+    }
 
-    /// Generates a tree
-    /// 0–1–2–3
-    /// └─10–11
-    fn simple_tree() -> Tree {
+    #[test]
+    fn test_add_node_chained() {
         let mut tree = Tree::new(0);
+        let node = tree.add_node_chained(0, 1).unwrap();
+        let node = tree.add_node_chained(node, 2).unwrap();
+        let node = tree.add_node_chained(node, 3).unwrap();
+        assert_eq!(node, 3);
+
+        let node = tree.add_node_chained(0, 10).unwrap();
+        tree.add_node_chained(node, 11).unwrap();
+
+        assert_eq!(tree, simple_tree());
+        assert!(matches!(
+            tree.add_node_chained(0, 3),
+            Err(TreeError::NodeAlreadyExists)
+        ));
+    }
 
+    #[test]
+    fn test_debug_is_canonical() {
+        let mut tree = Tree::new(0);
+        tree.add_node(0, 10).unwrap();
+        tree.add_node(10, 11).unwrap();
         tree.add_node(0, 1).unwrap();
         tree.add_node(1, 2).unwrap();
         tree.add_node(2, 3).unwrap();
 
-        tree.add_node(0, 10).unwrap();
-        tree.add_node(10, 11).unwrap();
-        tree
+        let debug = format!("{:?}", simple_tree());
+        assert_eq!(debug, format!("{:?}", tree));
+        assert_eq!(
+            debug,
+            "Tree { root: 0, edges: [(0, 1), (0, 10), (1, 2), (2, 3), (10, 11)], nodes: [0, 1, 2, 3, 10, 11] }"
+        );
     }
 
     #[test]
-    fn test_get_root() {
+    fn test_get_nodes() {
         let tree = simple_tree();
-        assert_eq!(tree.get_root(), 0);
+        assert_eq!(tree.get_nodes(), vec![0, 1, 2, 3, 10, 11]);
+    }
+
+    #[test]
+    fn test_get_leaves() {
+        assert_eq!(simple_tree().get_leaves(), vec![3, 11]);
+        assert_eq!(Tree::new(5).get_leaves(), vec![5]);
+    }
+
+    #[test]
+    fn test_preorder() {
+        let mut tree = simple_tree();
+        tree.add_node(1, 4).unwrap();
+        assert_eq!(tree.preorder(), vec![0, 1, 2, 3, 4, 10, 11]);
+        assert_eq!(Tree::new(5).preorder(), vec![5]);
+    }
+
+    #[test]
+    fn test_leaf_counts() {
+        let mut tree = simple_tree();
+        tree.add_node(1, 4).unwrap();
+
+        let counts = tree.leaf_counts();
+        assert_eq!(counts.len(), tree.len());
+        assert_eq!(counts[&0], tree.count_leaves());
+        assert_eq!(counts[&0], 3);
+        assert_eq!(counts[&1], 2);
+        assert_eq!(counts[&10], 1);
+        for leaf in tree.get_leaves() {
+            assert_eq!(counts[&leaf], 1);
+        }
+        assert_eq!(Tree::new(0).leaf_counts(), HashMap::from([(0, 1)]));
+    }
+
+    #[test]
+    fn test_get_children() {
+        let tree = simple_tree();
+        assert_eq!(tree.get_children(0), vec![1, 10]);
+        assert_eq!(tree.get_children(3), Vec::<Node>::new());
+        assert_eq!(tree.get_children(42), Vec::<Node>::new());
+    }
+
+    #[test]
+    fn test_children_sorted_by() {
+        let mut tree = simple_tree();
+        tree.add_node(0, 5).unwrap();
+        tree.add_node(0, 20).unwrap();
+        tree.add_node(20, 21).unwrap();
+
+        let size = |node| tree.subtree_size(node).unwrap();
+        assert_eq!(
+            tree.children_sorted_by(0, size).unwrap(),
+            vec![5, 10, 20, 1]
+        );
+        assert_eq!(
+            tree.children_sorted_by(0, |node| std::cmp::Reverse(size(node)))
+                .unwrap(),
+            vec![1, 10, 20, 5]
+        );
+        assert_eq!(
+            tree.children_sorted_by(3, size).unwrap(),
+            Vec::<Node>::new()
+        );
+        assert!(matches!(
+            tree.children_sorted_by(42, size),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_edges() {
+        let tree = simple_tree();
+        assert_eq!(
+            tree.edges(),
+            vec![(0, 1), (0, 10), (1, 2), (2, 3), (10, 11)]
+        );
     }
 
     mod test_prune_and_reattach {
@@ -486,6 +1309,52 @@ mod tests {
         }
     }
 
+    mod test_prune_and_reattach_errors {
+        use super::*;
+        use rand::SeedableRng;
+
+        #[test]
+        fn deep_chain() {
+            let nodes: Vec<Node> = (0..500).collect();
+            let mut tree = crate::tree::create_chain_tree(nodes).unwrap();
+
+            assert!(matches!(
+                tree.prune_and_reattach(1, 499),
+                Err(TreeError::TopologyError)
+            ));
+            assert!(matches!(
+                tree.prune_and_reattach(250, 250),
+                Err(TreeError::NodeAlreadyExists)
+            ));
+            assert!(matches!(
+                tree.prune_and_reattach(250, 1000),
+                Err(TreeError::NodeNotFound)
+            ));
+            tree.prune_and_reattach(499, 1).unwrap();
+            assert!(tree.is_valid());
+        }
+
+        #[test]
+        fn agrees_with_descendants() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(7);
+            let tree = crate::tree::stress_tree(&mut rng, 30, 20);
+
+            for node in tree.get_nodes() {
+                let descendants = tree.get_descendants(node);
+                for new_parent in tree.get_nodes() {
+                    let result = tree.clone().prune_and_reattach(node, new_parent);
+                    if node == new_parent {
+                        assert!(matches!(result, Err(TreeError::NodeAlreadyExists)));
+                    } else if descendants.contains(&new_parent) {
+                        assert!(matches!(result, Err(TreeError::TopologyError)));
+                    } else {
+                        assert!(result.is_ok());
+                    }
+                }
+            }
+        }
+    }
+
     mod test_swap_label {
         use super::*;
 
@@ -569,6 +1438,47 @@ mod tests {
             assert_eq!(tree, new_tree);
         }
 
+        /// Checks the root invariants after swapping the root with
+        /// a deep, non-adjacent node (in either order of the arguments).
+        fn check_root_swap(i: Node, j: Node, expected: &Tree) {
+            let mut new_tree = simple_tree();
+            new_tree.swap_labels(i, j).unwrap();
+
+            assert_eq!(new_tree, *expected);
+            assert_eq!(new_tree.get_parent(new_tree.get_root()), None);
+            assert!(new_tree.is_valid());
+        }
+
+        #[test]
+        fn swap_0_3() {
+            let mut tree = Tree::new(3); // Node 0 becomes 3 (new root)
+
+            tree.add_node(3, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 0).unwrap(); // Node 3 becomes 0 (a leaf)
+
+            tree.add_node(3, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+
+            check_root_swap(0, 3, &tree);
+            check_root_swap(3, 0, &tree);
+        }
+
+        #[test]
+        fn swap_0_11() {
+            let mut tree = Tree::new(11); // Node 0 becomes 11 (new root)
+
+            tree.add_node(11, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 3).unwrap();
+
+            tree.add_node(11, 10).unwrap();
+            tree.add_node(10, 0).unwrap(); // Node 11 becomes 0 (a leaf)
+
+            check_root_swap(0, 11, &tree);
+            check_root_swap(11, 0, &tree);
+        }
+
         #[test]
         fn swap_1_3() {
             let mut tree = Tree::new(0);
@@ -586,4 +1496,731 @@ mod tests {
             assert_eq!(tree, new_tree);
         }
     }
+
+    mod test_reroot_at_outgroup {
+        use super::*;
+
+        #[test]
+        fn reroot_at_3() {
+            let mut tree = Tree::new(12);
+
+            tree.add_node(12, 3).unwrap();
+            tree.add_node(12, 2).unwrap();
+            tree.add_node(2, 1).unwrap();
+            tree.add_node(1, 0).unwrap();
+            tree.add_node(0, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+
+            let mut new_tree = simple_tree();
+            new_tree.reroot_at_outgroup(3).unwrap();
+
+            assert_eq!(tree, new_tree);
+            assert!(new_tree.is_valid());
+            assert_eq!(new_tree.get_root(), 12);
+            assert!(new_tree.is_child(3, 12));
+            assert_eq!(new_tree.get_parent(12), None);
+        }
+
+        #[test]
+        fn reroot_at_child_of_root() {
+            let mut tree = Tree::new(12);
+
+            tree.add_node(12, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+            tree.add_node(12, 0).unwrap();
+            tree.add_node(0, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 3).unwrap();
+
+            let mut new_tree = simple_tree();
+            new_tree.reroot_at_outgroup(10).unwrap();
+
+            assert_eq!(tree, new_tree);
+            assert!(new_tree.is_valid());
+        }
+
+        #[test]
+        fn reroot_near_label_ceiling() {
+            let mut tree = Tree::new(Node::MAX);
+            tree.add_node(Node::MAX, 0).unwrap();
+            tree.add_node(0, Node::MAX - 1).unwrap();
+
+            // Both `Node::MAX + 1` and 0 are not available.
+            tree.reroot_at_outgroup(Node::MAX - 1).unwrap();
+            assert_eq!(tree.get_root(), 1);
+            assert!(tree.is_child(Node::MAX - 1, 1));
+            assert!(tree.is_valid());
+        }
+
+        #[test]
+        fn reroot_at_root_fails() {
+            let mut tree = simple_tree();
+            assert!(matches!(
+                tree.reroot_at_outgroup(0),
+                Err(TreeError::TopologyError)
+            ));
+        }
+
+        #[test]
+        fn reroot_at_missing_fails() {
+            let mut tree = simple_tree();
+            assert!(matches!(
+                tree.reroot_at_outgroup(42),
+                Err(TreeError::NodeNotFound)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_level_sizes() {
+        let tree = simple_tree();
+        assert_eq!(tree.level_sizes(), vec![1, 2, 2, 1]);
+        assert_eq!(tree.level_sizes().len(), tree.calculate_height());
+        assert_eq!(Tree::new(0).level_sizes(), vec![1]);
+    }
+
+    #[test]
+    fn test_depth_profile() {
+        let mut tree = simple_tree();
+        tree.add_node(1, 4).unwrap();
+        tree.add_node(4, 5).unwrap();
+        tree.add_node(4, 6).unwrap();
+
+        assert_eq!(tree.depth_profile(1).unwrap(), vec![1, 2, 3]);
+        assert_eq!(tree.depth_profile(10).unwrap(), vec![1, 1]);
+        assert_eq!(tree.depth_profile(3).unwrap(), vec![1]);
+        assert_eq!(tree.depth_profile(0).unwrap(), tree.level_sizes());
+        assert!(matches!(
+            tree.depth_profile(42),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_width() {
+        use crate::tree::{create_chain_tree, create_star_tree};
+
+        assert_eq!(simple_tree().width(), 2);
+        assert_eq!(create_star_tree(0, [1, 2, 3, 4, 5]).unwrap().width(), 5);
+        assert_eq!(create_chain_tree([0, 1, 2, 3, 4]).unwrap().width(), 1);
+        assert_eq!(Tree::new(0).width(), 1);
+    }
+
+    #[test]
+    fn test_find() {
+        let tree = simple_tree();
+        // Breadth-first order: 0, 1, 10, 2, 11, 3
+        assert_eq!(tree.find(0, |node| node > 1).unwrap(), Some(10));
+        assert_eq!(tree.find(0, |node| node > 10).unwrap(), Some(11));
+        assert_eq!(tree.find(1, |node| node > 2).unwrap(), Some(3));
+        assert_eq!(tree.find(1, |node| node > 10).unwrap(), None);
+        assert!(matches!(
+            tree.find(42, |_| true),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_is_ancestor() {
+        let tree = simple_tree();
+        assert!(tree.is_ancestor(0, 3));
+        assert!(tree.is_ancestor(1, 3));
+        assert!(tree.is_ancestor(10, 11));
+        assert!(!tree.is_ancestor(3, 3));
+        assert!(!tree.is_ancestor(3, 1));
+        assert!(!tree.is_ancestor(10, 3));
+        assert!(!tree.is_ancestor(42, 3));
+    }
+
+    #[test]
+    fn test_extreme_leaves() {
+        let mut tree = simple_tree();
+        assert_eq!(tree.deepest_leaf(), (3, 3));
+        assert_eq!(tree.shallowest_leaf(), (11, 2));
+
+        // Ties are broken by the smallest label.
+        tree.add_node(10, 4).unwrap();
+        tree.add_node(11, 5).unwrap();
+        tree.add_node(0, 20).unwrap();
+        tree.add_node(0, 7).unwrap();
+        assert_eq!(tree.deepest_leaf(), (3, 3));
+        assert_eq!(tree.shallowest_leaf(), (7, 1));
+
+        assert_eq!(Tree::new(0).deepest_leaf(), (0, 0));
+        assert_eq!(Tree::new(0).shallowest_leaf(), (0, 0));
+    }
+
+    #[test]
+    fn test_balanced_child_order() {
+        // Children of the root have subtrees of sizes 1, ..., 5.
+        let mut tree = Tree::new(0);
+        for (child, size) in [(1, 3), (2, 1), (3, 5), (4, 2), (5, 4)] {
+            let mut current = tree.add_node_chained(0, child).unwrap();
+            for i in 1..size {
+                current = tree.add_node_chained(current, 10 * child + i).unwrap();
+            }
+        }
+
+        let order = tree.balanced_child_order(0).unwrap();
+        assert_eq!(order, vec![3, 5, 4, 1, 2]);
+
+        assert_eq!(simple_tree().balanced_child_order(0).unwrap(), vec![1, 10]);
+        assert_eq!(
+            simple_tree().balanced_child_order(3).unwrap(),
+            Vec::<Node>::new()
+        );
+        assert!(matches!(
+            tree.balanced_child_order(42),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_is_path() {
+        use crate::tree::{create_chain_tree, create_star_tree};
+
+        assert!(create_chain_tree([0, 1, 2, 3]).unwrap().is_path());
+        assert!(Tree::new(0).is_path());
+        assert!(create_star_tree(0, [1]).unwrap().is_path());
+        assert!(!create_star_tree(0, [1, 2]).unwrap().is_path());
+        assert!(!simple_tree().is_path());
+    }
+
+    #[test]
+    fn test_lineage() {
+        let tree = simple_tree();
+        let edges: Vec<(Node, Node)> = tree.lineage(3).unwrap().collect();
+        assert_eq!(edges, vec![(3, 2), (2, 1), (1, 0)]);
+
+        assert_eq!(tree.lineage(0).unwrap().count(), 0);
+        // The iterator is lazy, so it can stop early.
+        assert_eq!(
+            tree.lineage(3).unwrap().find(|&(_, p)| p == 2),
+            Some((3, 2))
+        );
+        assert!(matches!(tree.lineage(42), Err(TreeError::NodeNotFound)));
+    }
+
+    #[test]
+    fn test_fold_path_to_root() {
+        let tree = simple_tree();
+        for node in tree.get_nodes() {
+            let depth = tree
+                .fold_path_to_root(node, 0, |acc, _, _| acc + 1)
+                .unwrap();
+            assert_eq!(depth, tree.depth(node).unwrap());
+        }
+
+        let product = tree
+            .fold_path_to_root(3, 1.0, |acc, child, _| acc * 0.5_f64.powi(child as i32))
+            .unwrap();
+        assert_eq!(product, 0.5_f64.powi(6));
+
+        assert!(matches!(
+            tree.fold_path_to_root(42, 0, |acc, _, _| acc),
+            Err(TreeError::NodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_depth() {
+        let tree = simple_tree();
+        assert_eq!(tree.depth(0).unwrap(), 0);
+        assert_eq!(tree.depth(10).unwrap(), 1);
+        assert_eq!(tree.depth(3).unwrap(), 3);
+        assert!(matches!(tree.depth(42), Err(TreeError::NodeNotFound)));
+    }
+
+    mod test_shared_lineage {
+        use super::*;
+
+        #[test]
+        fn leaves_share_only_root() {
+            let tree = simple_tree();
+            assert_eq!(tree.shared_lineage(&[3, 11]).unwrap(), (0, 0));
+        }
+
+        #[test]
+        fn deeper_branch() {
+            let mut tree = simple_tree();
+            tree.add_node(1, 4).unwrap();
+            tree.add_node(2, 5).unwrap();
+
+            assert_eq!(tree.shared_lineage(&[3, 5]).unwrap(), (2, 2));
+            assert_eq!(tree.shared_lineage(&[3, 4, 5]).unwrap(), (1, 1));
+            assert_eq!(tree.shared_lineage(&[2, 3]).unwrap(), (2, 2));
+            assert_eq!(tree.shared_lineage(&[3]).unwrap(), (3, 3));
+        }
+
+        #[test]
+        fn missing_node() {
+            let tree = simple_tree();
+            assert!(matches!(
+                tree.shared_lineage(&[3, 42]),
+                Err(TreeError::NodeNotFound)
+            ));
+        }
+
+        #[test]
+        fn empty_input() {
+            let tree = simple_tree();
+            assert!(matches!(
+                tree.shared_lineage(&[]),
+                Err(TreeError::TopologyError)
+            ));
+        }
+    }
+
+    mod test_subtree_signature {
+        use super::*;
+
+        #[test]
+        fn isomorphic_subtrees() {
+            // 0–1–2–3
+            //   └─4
+            // └─10–11
+            //    └─12–13
+            let mut tree = Tree::new(0);
+            tree.add_node(0, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 3).unwrap();
+            tree.add_node(1, 4).unwrap();
+
+            tree.add_node(0, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+            tree.add_node(10, 12).unwrap();
+            tree.add_node(12, 13).unwrap();
+
+            let sig = |node| tree.subtree_signature(node).unwrap();
+            assert_eq!(sig(1), sig(10));
+            assert_eq!(sig(2), sig(12));
+            assert_eq!(sig(3), sig(11));
+            assert_ne!(sig(1), sig(2));
+            assert_ne!(sig(0), sig(1));
+        }
+
+        #[test]
+        fn chain_and_star_differ() {
+            let mut star = Tree::new(0);
+            star.add_node(0, 1).unwrap();
+            star.add_node(0, 2).unwrap();
+
+            let mut chain = Tree::new(0);
+            chain.add_node(0, 1).unwrap();
+            chain.add_node(1, 2).unwrap();
+
+            assert_ne!(
+                star.subtree_signature(0).unwrap(),
+                chain.subtree_signature(0).unwrap()
+            );
+        }
+
+        #[test]
+        fn missing_node() {
+            let tree = simple_tree();
+            assert!(matches!(
+                tree.subtree_signature(42),
+                Err(TreeError::NodeNotFound)
+            ));
+        }
+    }
+
+    mod test_collapse_unary_chains {
+        use super::*;
+
+        #[test]
+        fn chain_from_root() {
+            // 0–1–2–3
+            //     └─4
+            let mut tree = Tree::new(0);
+            tree.add_node(0, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 3).unwrap();
+            tree.add_node(2, 4).unwrap();
+
+            assert_eq!(tree.collapse_unary_chains(), 2);
+
+            let mut expected = Tree::new(0);
+            expected.add_node(0, 3).unwrap();
+            expected.add_node(0, 4).unwrap();
+            assert_eq!(tree, expected);
+            assert!(tree.is_valid());
+        }
+
+        #[test]
+        fn chain_in_the_middle() {
+            // 0–1–2–3–4
+            // │       └─6
+            // └─5
+            let mut tree = Tree::new(0);
+            tree.add_node(0, 5).unwrap();
+            tree.add_node(0, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 3).unwrap();
+            tree.add_node(3, 4).unwrap();
+            tree.add_node(3, 6).unwrap();
+
+            assert_eq!(tree.collapse_unary_chains(), 2);
+
+            let mut expected = Tree::new(0);
+            expected.add_node(0, 5).unwrap();
+            expected.add_node(0, 1).unwrap();
+            expected.add_node(1, 4).unwrap();
+            expected.add_node(1, 6).unwrap();
+            assert_eq!(tree, expected);
+            assert!(tree.is_valid());
+        }
+
+        #[test]
+        fn leaves_are_kept() {
+            let mut tree = simple_tree();
+            // 0–1–2–3 becomes 0–1–3 and 0–10–11 is left unchanged.
+            assert_eq!(tree.collapse_unary_chains(), 1);
+            assert_eq!(tree.edges(), vec![(0, 1), (0, 10), (1, 3), (10, 11)]);
+            assert!(tree.is_valid());
+        }
+    }
+
+    mod test_replace_label {
+        use super::*;
+
+        #[test]
+        fn replace_leaf() {
+            let mut tree = Tree::new(0);
+            tree.add_node(0, 1).unwrap();
+            tree.add_node(1, 2).unwrap();
+            tree.add_node(2, 30).unwrap();
+            tree.add_node(0, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+
+            let mut new_tree = simple_tree();
+            new_tree.replace_label(3, 30).unwrap();
+            assert_eq!(tree, new_tree);
+        }
+
+        #[test]
+        fn replace_internal() {
+            let mut tree = Tree::new(0);
+            tree.add_node(0, 5).unwrap();
+            tree.add_node(5, 2).unwrap();
+            tree.add_node(2, 3).unwrap();
+            tree.add_node(0, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+
+            let mut new_tree = simple_tree();
+            new_tree.replace_label(1, 5).unwrap();
+            assert_eq!(tree, new_tree);
+            assert!(new_tree.is_valid());
+        }
+
+        #[test]
+        fn replace_root() {
+            let mut new_tree = simple_tree();
+            new_tree.replace_label(0, 7).unwrap();
+            assert_eq!(new_tree.get_root(), 7);
+            assert_eq!(new_tree.get_children(7), vec![1, 10]);
+            assert!(new_tree.is_valid());
+        }
+
+        #[test]
+        fn errors() {
+            let mut tree = simple_tree();
+            assert!(matches!(
+                tree.replace_label(42, 43),
+                Err(TreeError::NodeNotFound)
+            ));
+            assert!(matches!(
+                tree.replace_label(1, 10),
+                Err(TreeError::NodeAlreadyExists)
+            ));
+            assert_eq!(tree, simple_tree());
+        }
+    }
+
+    mod test_serde {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            for tree in [simple_tree(), Tree::new(4)] {
+                let serialized = serde_json::to_string(&tree).unwrap();
+                let deserialized: Tree = serde_json::from_str(&serialized).unwrap();
+                assert_eq!(deserialized, tree);
+                assert!(deserialized.is_valid());
+            }
+        }
+
+        #[test]
+        fn round_trip_after_moves() {
+            let mut tree = simple_tree();
+            tree.prune_and_reattach(2, 10).unwrap();
+            tree.swap_labels(0, 3).unwrap();
+
+            let serialized = serde_json::to_string(&tree).unwrap();
+            let deserialized: Tree = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(deserialized, tree);
+        }
+    }
+
+    mod test_random_nodes {
+        use super::*;
+        use rand::SeedableRng;
+
+        /// Draws `n` samples and checks that they are (approximately) uniform over `expected`.
+        fn check_uniform(mut draw: impl FnMut() -> Node, expected: &[Node]) {
+            let n = 30_000;
+            let mut counts: HashMap<Node, usize> = HashMap::new();
+            for _ in 0..n {
+                *counts.entry(draw()).or_default() += 1;
+            }
+
+            let mut drawn: Vec<Node> = counts.keys().copied().collect();
+            drawn.sort();
+            assert_eq!(drawn, expected);
+            for count in counts.values() {
+                let frequency = *count as f64 / n as f64;
+                assert!((frequency - 1.0 / expected.len() as f64).abs() < 0.02);
+            }
+        }
+
+        #[test]
+        fn random_leaf() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+            let mut tree = simple_tree();
+            tree.add_node(0, 20).unwrap();
+            check_uniform(|| tree.random_leaf(&mut rng).unwrap(), &[3, 11, 20]);
+        }
+
+        #[test]
+        fn random_internal_node() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+            let tree = simple_tree();
+            check_uniform(
+                || tree.random_internal_node(&mut rng).unwrap(),
+                &[0, 1, 2, 10],
+            );
+        }
+
+        #[test]
+        fn single_node() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+            let tree = Tree::new(5);
+            assert_eq!(tree.random_leaf(&mut rng), Some(5));
+            assert_eq!(tree.random_internal_node(&mut rng), None);
+        }
+    }
+
+    mod test_contains_subtree {
+        use super::*;
+
+        #[test]
+        fn subtree_is_contained() {
+            let tree = simple_tree();
+
+            let mut subtree = Tree::new(1);
+            subtree.add_node(1, 2).unwrap();
+            subtree.add_node(2, 3).unwrap();
+            assert_eq!(tree.contains_subtree(&subtree), Some(1));
+
+            assert_eq!(tree.contains_subtree(&Tree::new(11)), Some(11));
+            assert_eq!(tree.contains_subtree(&tree), Some(0));
+        }
+
+        #[test]
+        fn relabeled_is_not_contained() {
+            let tree = simple_tree();
+
+            let mut subtree = Tree::new(1);
+            subtree.add_node(1, 3).unwrap();
+            subtree.add_node(3, 2).unwrap();
+            assert_eq!(tree.contains_subtree(&subtree), None);
+
+            let mut subtree = Tree::new(1);
+            subtree.add_node(1, 2).unwrap();
+            subtree.add_node(2, 4).unwrap();
+            assert_eq!(tree.contains_subtree(&subtree), None);
+        }
+
+        #[test]
+        fn partial_subtree_is_not_contained() {
+            let tree = simple_tree();
+
+            let mut subtree = Tree::new(1);
+            subtree.add_node(1, 2).unwrap();
+            assert_eq!(tree.contains_subtree(&subtree), None);
+            assert_eq!(tree.contains_subtree(&Tree::new(42)), None);
+        }
+    }
+
+    mod test_num_cherries {
+        use super::*;
+        use crate::tree::{create_chain_tree, create_star_tree};
+
+        #[test]
+        fn balanced_binary_tree() {
+            // 0 ─ 1 ─ {3, 4}
+            //   └ 2 ─ {5, 6}
+            let mut tree = Tree::new(0);
+            for (parent, child) in [(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (2, 6)] {
+                tree.add_node(parent, child).unwrap();
+            }
+            assert_eq!(tree.num_cherries(), 2);
+        }
+
+        #[test]
+        fn chain_and_star() {
+            assert_eq!(create_chain_tree([0, 1, 2, 3]).unwrap().num_cherries(), 0);
+            assert_eq!(Tree::new(0).num_cherries(), 0);
+            assert_eq!(create_star_tree(0, [1, 2]).unwrap().num_cherries(), 1);
+            assert_eq!(create_star_tree(0, [1, 2, 3]).unwrap().num_cherries(), 0);
+            assert_eq!(simple_tree().num_cherries(), 0);
+        }
+    }
+
+    mod test_normalize_root {
+        use super::*;
+        use crate::tree::create_chain_tree;
+
+        #[test]
+        fn promotes_single_children() {
+            // 5–0–1–2–3
+            //     └─10–11
+            let mut tree = create_chain_tree([5, 0, 1, 2, 3]).unwrap();
+            tree.add_node(0, 10).unwrap();
+            tree.add_node(10, 11).unwrap();
+            assert_eq!(tree.len(), 7);
+
+            assert!(tree.normalize_root());
+            assert_eq!(tree.get_root(), 0);
+            assert_eq!(tree.len(), 6);
+            assert!(tree.is_valid());
+            assert_eq!(tree.edges(), simple_tree().edges());
+        }
+
+        #[test]
+        fn branching_root_is_unchanged() {
+            let mut tree = simple_tree();
+            assert!(!tree.normalize_root());
+            assert_eq!(tree.edges(), simple_tree().edges());
+
+            let mut single = Tree::new(4);
+            assert!(!single.normalize_root());
+            assert_eq!(single.get_root(), 4);
+        }
+
+        #[test]
+        fn chain_collapses_to_leaf() {
+            let mut tree = create_chain_tree([0, 1, 2, 3]).unwrap();
+            assert!(tree.normalize_root());
+            assert_eq!(tree.get_root(), 3);
+            assert_eq!(tree.len(), 1);
+            assert!(tree.is_valid());
+        }
+    }
+
+    mod test_shuffle_labels {
+        use super::*;
+        use rand::SeedableRng;
+
+        #[test]
+        fn permute_labels_rejects_collisions() {
+            let mapping = HashMap::from([(1, 2)]);
+            assert!(matches!(
+                simple_tree().permute_labels(&mapping),
+                Err(TreeError::NodeAlreadyExists)
+            ));
+        }
+
+        #[test]
+        fn permute_labels_renames_nodes() {
+            let mapping = HashMap::from([(0, 100), (3, 300)]);
+            let tree = simple_tree().permute_labels(&mapping).unwrap();
+            assert!(tree.is_valid());
+            assert_eq!(tree.get_root(), 100);
+            assert_eq!(tree.get_parent(300), Some(2));
+            assert_eq!(tree.get_children(100), vec![1, 10]);
+        }
+
+        #[test]
+        fn shuffle_is_isomorphic_and_reversible() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+            let tree = simple_tree();
+
+            for _ in 0..20 {
+                let (shuffled, inverse) = tree.shuffle_labels(&mut rng);
+                assert!(shuffled.is_valid());
+                assert_eq!(shuffled.get_nodes(), tree.get_nodes());
+                assert_eq!(
+                    shuffled.subtree_signature(shuffled.get_root()).unwrap(),
+                    tree.subtree_signature(tree.get_root()).unwrap()
+                );
+
+                let restored = shuffled.permute_labels(&inverse).unwrap();
+                assert_eq!(restored, tree);
+            }
+        }
+    }
+
+    mod test_move_subtree_up {
+        use super::*;
+
+        #[test]
+        fn raise_deep_node() {
+            let mut tree = simple_tree();
+            tree.move_subtree_up(3).unwrap();
+            assert!(tree.is_valid());
+            assert_eq!(tree.get_parent(3), Some(1));
+            assert_eq!(tree.get_children(1), vec![2, 3]);
+            assert!(tree.get_children(2).is_empty());
+
+            // The subtree moves as a whole.
+            tree.move_subtree_up(2).unwrap();
+            tree.move_subtree_up(11).unwrap();
+            assert!(tree.is_valid());
+            assert_eq!(tree.get_children(0), vec![1, 2, 10, 11]);
+        }
+
+        #[test]
+        fn no_grandparent() {
+            let mut tree = simple_tree();
+            assert!(matches!(
+                tree.move_subtree_up(0),
+                Err(TreeError::TopologyError)
+            ));
+            assert!(matches!(
+                tree.move_subtree_up(10),
+                Err(TreeError::TopologyError)
+            ));
+            assert!(matches!(
+                tree.move_subtree_up(42),
+                Err(TreeError::NodeNotFound)
+            ));
+            assert_eq!(tree.edges(), simple_tree().edges());
+        }
+    }
+
+    mod test_normalize_children_map {
+        use super::*;
+
+        #[test]
+        fn removes_empty_sets() {
+            let mut tree = simple_tree();
+            tree.children.insert(3, HashSet::new());
+            tree.children.insert(11, HashSet::new());
+            assert_ne!(tree, simple_tree());
+            assert!(!tree.get_leaves().contains(&3));
+
+            assert_eq!(tree.normalize_children_map(), 2);
+            assert_eq!(tree, simple_tree());
+            assert_eq!(tree.get_leaves(), vec![3, 11]);
+            assert!(tree.is_valid());
+        }
+
+        #[test]
+        fn canonical_map_is_unchanged() {
+            let mut tree = simple_tree();
+            assert_eq!(tree.normalize_children_map(), 0);
+            assert_eq!(tree, simple_tree());
+        }
+    }
 }
@@ -0,0 +1,58 @@
+/// Writing trees in plain-text formats used by other tools.
+///
+/// Newick is handled separately, in the `newick` module.
+/// All the formats below keep the integer labels as they are.
+use super::core::Tree;
+
+impl Tree {
+    /// Writes the tree as a tab-separated edge list with the header `from\tto`,
+    /// one `parent\tchild` line per edge, sorted.
+    /// This can be read, e.g., with `read.delim` in R and passed to `igraph::graph_from_data_frame`.
+    ///
+    /// A tree with a single node has only the header.
+    pub fn to_edge_tsv(&self) -> String {
+        let mut out = String::from("from\tto\n");
+        for (parent, child) in self.edges() {
+            out.push_str(&format!("{}\t{}\n", parent, child));
+        }
+        out
+    }
+
+    /// Writes the tree as a tab-separated table with the header `node\tparent`,
+    /// one line per node in ascending order.
+    /// The parent of the root is written as `NA`, which R reads as a missing value.
+    pub fn to_parent_tsv(&self) -> String {
+        let mut out = String::from("node\tparent\n");
+        for node in self.get_nodes() {
+            match self.get_parent(node) {
+                Some(parent) => out.push_str(&format!("{}\t{}\n", node, parent)),
+                None => out.push_str(&format!("{}\tNA\n", node)),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::test_utils::simple_tree;
+
+    #[test]
+    fn edge_tsv() {
+        assert_eq!(
+            simple_tree().to_edge_tsv(),
+            "from\tto\n0\t1\n0\t10\n1\t2\n2\t3\n10\t11\n"
+        );
+        assert_eq!(Tree::new(4).to_edge_tsv(), "from\tto\n");
+    }
+
+    #[test]
+    fn parent_tsv() {
+        assert_eq!(
+            simple_tree().to_parent_tsv(),
+            "node\tparent\n0\tNA\n1\t0\n2\t1\n3\t2\n10\t0\n11\t10\n"
+        );
+        assert_eq!(Tree::new(4).to_parent_tsv(), "node\tparent\n4\tNA\n");
+    }
+}
@@ -0,0 +1,199 @@
+/// Utilities for comparing and summarizing trees.
+use super::core::{Node, Tree};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Edge `(parent, child)` of a tree.
+pub type Edge = (Node, Node);
+
+/// Compares the edges of two trees (which may have different node sets).
+/// Returns a pair `(edges_only_in_a, edges_only_in_b)`, with both vectors sorted.
+pub fn tree_diff(a: &Tree, b: &Tree) -> (Vec<Edge>, Vec<Edge>) {
+    let edges_a = a.edges();
+    let edges_b = b.edges();
+
+    let set_a: HashSet<Edge> = edges_a.iter().copied().collect();
+    let set_b: HashSet<Edge> = edges_b.iter().copied().collect();
+
+    let only_in_a = edges_a.into_iter().filter(|e| !set_b.contains(e)).collect();
+    let only_in_b = edges_b.into_iter().filter(|e| !set_a.contains(e)).collect();
+    (only_in_a, only_in_b)
+}
+
+/// Maps the labels of the tree to consecutive indices `0, 1, ..., n-1`,
+/// preserving the order of the labels.
+pub fn compact_labels(tree: &Tree) -> HashMap<Node, usize> {
+    tree.get_nodes()
+        .into_iter()
+        .enumerate()
+        .map(|(index, node)| (node, index))
+        .collect()
+}
+
+/// Returns the subtree sizes (see `Tree::subtree_size`) as a vector indexed
+/// by the compacted labels, together with the map from labels to indices
+/// (see `compact_labels`).
+pub fn subtree_sizes_compact(tree: &Tree) -> (Vec<usize>, HashMap<Node, usize>) {
+    let index = compact_labels(tree);
+
+    // Visiting the nodes in reverse pre-order gives a post-order pass.
+    let mut sizes = vec![1; index.len()];
+    for &node in tree.preorder().iter().rev() {
+        if let Some(parent) = tree.get_parent(node) {
+            sizes[index[&parent]] += sizes[index[&node]];
+        }
+    }
+    (sizes, index)
+}
+
+/// For each pair of nodes `(a, b)` returns the number of leaves which are
+/// in both the subtree rooted at `a` and the subtree rooted at `b`.
+/// Two subtrees intersect only if they are nested, so only the pairs in which
+/// one node is an ancestor of (or equal to) the other are stored, in both orders.
+/// A missing key means that the overlap is 0.
+///
+/// The map has `O(n * depth)` entries, rather than `n^2`.
+pub fn subtree_leaf_overlap(tree: &Tree) -> HashMap<(Node, Node), usize> {
+    let leaf_counts = tree.leaf_counts();
+
+    let mut overlap = HashMap::new();
+    // For nested pairs the intersection is the subtree of the deeper node.
+    for node in tree.preorder() {
+        overlap.insert((node, node), leaf_counts[&node]);
+        for (_, ancestor) in tree.lineage(node).unwrap() {
+            overlap.insert((ancestor, node), leaf_counts[&node]);
+            overlap.insert((node, ancestor), leaf_counts[&node]);
+        }
+    }
+    overlap
+}
+
+/// Checks whether two clades (sets of nodes) are compatible,
+/// i.e., whether they are disjoint or one is contained in the other.
+/// Only compatible clades can appear together in a single tree.
+pub fn clades_compatible(a: &BTreeSet<Node>, b: &BTreeSet<Node>) -> bool {
+    a.is_disjoint(b) || a.is_subset(b) || b.is_subset(a)
+}
+
+/// Checks whether all the clades are pairwise compatible (see `clades_compatible`).
+pub fn all_compatible(clades: &[BTreeSet<Node>]) -> bool {
+    clades
+        .iter()
+        .enumerate()
+        .all(|(i, a)| clades[i + 1..].iter().all(|b| clades_compatible(a, b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::create_chain_tree;
+    use crate::tree::test_utils::simple_tree;
+
+    #[test]
+    fn test_compact_labels() {
+        let tree = create_chain_tree([10, 3, 7]).unwrap();
+        assert_eq!(
+            compact_labels(&tree),
+            HashMap::from([(3, 0), (7, 1), (10, 2)])
+        );
+    }
+
+    #[test]
+    fn test_subtree_sizes_compact() {
+        let mut tree = simple_tree();
+        tree.add_node(1, 7).unwrap();
+
+        let (sizes, index) = subtree_sizes_compact(&tree);
+        assert_eq!(sizes, vec![7, 4, 2, 1, 1, 2, 1]);
+        for node in tree.get_nodes() {
+            assert_eq!(sizes[index[&node]], tree.subtree_size(node).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_subtree_leaf_overlap() {
+        let mut tree = simple_tree();
+        tree.add_node(1, 7).unwrap();
+
+        let overlap = subtree_leaf_overlap(&tree);
+        // 7 nodes paired with themselves and 11 (ancestor, descendant) pairs in both orders.
+        assert_eq!(overlap.len(), 29);
+
+        // Nested pairs
+        assert_eq!(overlap[&(0, 0)], 3);
+        assert_eq!(overlap[&(0, 1)], 2);
+        assert_eq!(overlap[&(1, 0)], 2);
+        assert_eq!(overlap[&(1, 3)], 1);
+        assert_eq!(overlap[&(10, 11)], 1);
+        // Non-nested pairs
+        assert_eq!(overlap.get(&(1, 10)), None);
+        assert_eq!(overlap.get(&(2, 7)), None);
+        assert_eq!(overlap.get(&(3, 11)), None);
+    }
+
+    mod test_tree_diff {
+        use super::*;
+
+        #[test]
+        fn same_tree() {
+            let tree = create_chain_tree([0, 1, 2]).unwrap();
+            let (only_in_a, only_in_b) = tree_diff(&tree, &tree.clone());
+            assert!(only_in_a.is_empty());
+            assert!(only_in_b.is_empty());
+        }
+
+        #[test]
+        fn single_prune_and_reattach() {
+            let a = simple_tree();
+
+            let mut b = a.clone();
+            b.prune_and_reattach(2, 10).unwrap();
+
+            let (only_in_a, only_in_b) = tree_diff(&a, &b);
+            assert_eq!(only_in_a, vec![(1, 2)]);
+            assert_eq!(only_in_b, vec![(10, 2)]);
+        }
+
+        #[test]
+        fn different_node_sets() {
+            let a = create_chain_tree([0, 1, 2]).unwrap();
+            let b = create_chain_tree([0, 1, 5]).unwrap();
+
+            let (only_in_a, only_in_b) = tree_diff(&a, &b);
+            assert_eq!(only_in_a, vec![(1, 2)]);
+            assert_eq!(only_in_b, vec![(1, 5)]);
+        }
+    }
+
+    #[test]
+    fn test_clades_compatible() {
+        let clade = |nodes: &[Node]| nodes.iter().copied().collect::<BTreeSet<Node>>();
+
+        // Nested
+        assert!(clades_compatible(&clade(&[1, 2, 3]), &clade(&[2, 3])));
+        assert!(clades_compatible(&clade(&[2, 3]), &clade(&[1, 2, 3])));
+        assert!(clades_compatible(&clade(&[1, 2]), &clade(&[1, 2])));
+        // Disjoint
+        assert!(clades_compatible(&clade(&[1, 2]), &clade(&[3, 4])));
+        assert!(clades_compatible(&clade(&[]), &clade(&[3, 4])));
+        // Overlapping
+        assert!(!clades_compatible(&clade(&[1, 2]), &clade(&[2, 3])));
+    }
+
+    #[test]
+    fn test_all_compatible() {
+        let clade = |nodes: &[Node]| nodes.iter().copied().collect::<BTreeSet<Node>>();
+
+        assert!(all_compatible(&[]));
+        assert!(all_compatible(&[
+            clade(&[1, 2, 3, 4]),
+            clade(&[1, 2]),
+            clade(&[3, 4]),
+            clade(&[1]),
+        ]));
+        assert!(!all_compatible(&[
+            clade(&[1, 2, 3, 4]),
+            clade(&[1, 2]),
+            clade(&[2, 3]),
+        ]));
+    }
+}
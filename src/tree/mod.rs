@@ -1,5 +1,15 @@
 mod core;
+mod export;
+pub mod metrics;
+mod newick;
 mod ops;
+mod ordered;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
-pub use core::{Node, Tree};
-pub use ops::{create_chain_tree, create_star_tree};
+pub use core::{Node, Tree, TreeError};
+pub use newick::{from_newick, read_newick_file, NewickError};
+pub use ops::{
+    create_chain_tree, create_star_tree, from_children_map, num_rooted_labeled_trees, stress_tree,
+};
+pub use ordered::OrderedTree;
@@ -0,0 +1,314 @@
+/// Reading and writing trees in the Newick format.
+///
+/// All the nodes (including the internal ones) are labeled,
+/// e.g., the tree
+///   0–1–2
+///   └─10
+/// is written as `((2)1,10)0;`. Branch lengths are not supported.
+use super::core::{Node, Tree, TreeError};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Errors which can occur when reading a tree in the Newick format.
+#[derive(Debug)]
+pub enum NewickError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The string is not valid Newick. Contains the byte position of the problem.
+    Parse(usize),
+    /// The string is valid Newick, but does not describe a valid tree
+    /// (e.g., a label is repeated).
+    Tree(TreeError),
+}
+
+impl From<io::Error> for NewickError {
+    fn from(error: io::Error) -> Self {
+        NewickError::Io(error)
+    }
+}
+
+impl From<TreeError> for NewickError {
+    fn from(error: TreeError) -> Self {
+        NewickError::Tree(error)
+    }
+}
+
+impl Tree {
+    /// Writes the tree in the Newick format, with children in ascending order.
+    pub fn to_newick(&self) -> String {
+        self.to_newick_with(|_| String::new())
+    }
+
+    /// Writes the tree in the Newick format, with children in ascending order,
+    /// appending `suffix(node)` right after the label of every node
+    /// (e.g., an annotation in square brackets).
+    pub fn to_newick_with<F: Fn(Node) -> String>(&self, suffix: F) -> String {
+        /// Pending piece of the output. An explicit stack is used (rather than recursion),
+        /// so that deep trees do not overflow the call stack.
+        enum Step {
+            Open(Node),
+            Close(Node),
+            Comma,
+        }
+
+        let mut out = String::new();
+        let mut stack = vec![Step::Open(self.get_root())];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Open(node) => {
+                    let children = self.get_children(node);
+                    if children.is_empty() {
+                        out.push_str(&node.to_string());
+                        out.push_str(&suffix(node));
+                        continue;
+                    }
+                    out.push('(');
+                    stack.push(Step::Close(node));
+                    for (i, &child) in children.iter().enumerate().rev() {
+                        stack.push(Step::Open(child));
+                        if i > 0 {
+                            stack.push(Step::Comma);
+                        }
+                    }
+                }
+                Step::Close(node) => {
+                    out.push(')');
+                    out.push_str(&node.to_string());
+                    out.push_str(&suffix(node));
+                }
+                Step::Comma => out.push(','),
+            }
+        }
+        out.push(';');
+        out
+    }
+
+    /// Returns a string identifying the tree: two trees are equal
+    /// if and only if their canonical strings are equal.
+    /// As the children are written in ascending order, this is the Newick string.
+    pub fn canonical_string(&self) -> String {
+        self.to_newick()
+    }
+
+    /// Writes the tree in the Newick format to the file at `path`.
+    pub fn write_newick_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_newick() + "\n")
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.bytes.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), NewickError> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(NewickError::Parse(self.position))
+        }
+    }
+
+    fn label(&mut self) -> Result<Node, NewickError> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_digit() {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .unwrap()
+            .parse()
+            .map_err(|_| NewickError::Parse(start))
+    }
+
+    /// Parses a subtree, returning its root and the children of all its internal nodes.
+    /// The nesting is tracked with an explicit stack (rather than recursion),
+    /// so that deeply nested input does not overflow the call stack.
+    fn subtree(&mut self) -> Result<(Node, HashMap<Node, Vec<Node>>), NewickError> {
+        let mut seen = HashSet::new();
+        let mut children = HashMap::new();
+        // Children parsed so far for each parenthesis which is still open.
+        let mut open: Vec<Vec<Node>> = Vec::new();
+
+        loop {
+            while self.peek() == Some(b'(') {
+                self.position += 1;
+                open.push(Vec::new());
+            }
+            let mut label = self.label()?;
+            if !seen.insert(label) {
+                return Err(TreeError::NodeAlreadyExists.into());
+            }
+
+            // Close the parentheses until the next sibling (or the end of the subtree).
+            loop {
+                let Some(siblings) = open.last_mut() else {
+                    return Ok((label, children));
+                };
+                siblings.push(label);
+                if self.peek() == Some(b',') {
+                    self.position += 1;
+                    break;
+                }
+                self.expect(b')')?;
+
+                let node_children = open.pop().unwrap();
+                label = self.label()?;
+                if !seen.insert(label) {
+                    return Err(TreeError::NodeAlreadyExists.into());
+                }
+                children.insert(label, node_children);
+            }
+        }
+    }
+}
+
+/// Reads a tree from a string in the Newick format.
+pub fn from_newick(newick: &str) -> Result<Tree, NewickError> {
+    let mut parser = Parser {
+        bytes: newick.as_bytes(),
+        position: 0,
+    };
+    let (root, children) = parser.subtree()?;
+    parser.expect(b';')?;
+    if parser.peek().is_some() {
+        return Err(NewickError::Parse(parser.position));
+    }
+
+    let mut tree = Tree::new(root);
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        for &child in children.get(&node).into_iter().flatten() {
+            tree.add_node(node, child)?;
+            stack.push(child);
+        }
+    }
+    Ok(tree)
+}
+
+/// Reads a tree in the Newick format from the file at `path`.
+pub fn read_newick_file<P: AsRef<Path>>(path: P) -> Result<Tree, NewickError> {
+    from_newick(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::create_chain_tree;
+    use crate::tree::test_utils::simple_tree;
+
+    #[test]
+    fn write_simple_tree() {
+        assert_eq!(simple_tree().to_newick(), "(((3)2)1,(11)10)0;");
+        assert_eq!(Tree::new(4).to_newick(), "4;");
+    }
+
+    #[test]
+    fn write_with_suffix() {
+        let tree = simple_tree();
+        assert_eq!(
+            tree.to_newick_with(|node| if node % 2 == 1 {
+                "*".to_string()
+            } else {
+                String::new()
+            }),
+            "(((3*)2)1*,(11*)10)0;"
+        );
+        assert_eq!(tree.to_newick_with(|_| String::new()), tree.to_newick());
+    }
+
+    #[test]
+    fn canonical_string() {
+        let mut tree = Tree::new(0);
+        tree.add_node(0, 10).unwrap();
+        tree.add_node(0, 1).unwrap();
+        tree.add_node(10, 11).unwrap();
+        tree.add_node(1, 2).unwrap();
+        tree.add_node(2, 3).unwrap();
+        assert_eq!(tree.canonical_string(), simple_tree().canonical_string());
+
+        tree.prune_and_reattach(3, 0).unwrap();
+        assert_ne!(tree.canonical_string(), simple_tree().canonical_string());
+    }
+
+    #[test]
+    fn read_simple_tree() {
+        assert_eq!(from_newick("(((3)2)1,(11)10)0;").unwrap(), simple_tree());
+        assert_eq!(
+            from_newick(" ( (11) 10 , ((3)2)1 ) 0 ;\n").unwrap(),
+            simple_tree()
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            from_newick("(((3)2)1,(11)10)0"),
+            Err(NewickError::Parse(17))
+        ));
+        assert!(matches!(from_newick("(1,2;"), Err(NewickError::Parse(4))));
+        assert!(matches!(
+            from_newick("(1:0.5)0;"),
+            Err(NewickError::Parse(2))
+        ));
+        assert!(matches!(from_newick("(1,)0;"), Err(NewickError::Parse(3))));
+        assert!(matches!(from_newick("0;1"), Err(NewickError::Parse(2))));
+    }
+
+    #[test]
+    fn repeated_label() {
+        assert!(matches!(
+            from_newick("(1,1)0;"),
+            Err(NewickError::Tree(TreeError::NodeAlreadyExists))
+        ));
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let path = std::env::temp_dir().join(format!("blackforest-{}.nwk", std::process::id()));
+
+        let tree = simple_tree();
+        tree.write_newick_file(&path).unwrap();
+        let read = read_newick_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, tree);
+    }
+
+    #[test]
+    fn deep_chain_round_trip() {
+        // Deep enough to overflow the call stack if traversed recursively.
+        let tree = create_chain_tree(0..20_000).unwrap();
+        let newick = tree.to_newick();
+        assert!(newick.starts_with("((((("));
+        assert_eq!(from_newick(&newick).unwrap(), tree);
+
+        assert!(matches!(
+            from_newick(&"(".repeat(100_000)),
+            Err(NewickError::Parse(100_000))
+        ));
+    }
+
+    #[test]
+    fn missing_file() {
+        assert!(matches!(
+            read_newick_file("/this/file/does/not/exist.nwk"),
+            Err(NewickError::Io(_))
+        ));
+    }
+}
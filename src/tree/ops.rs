@@ -1,4 +1,7 @@
 use super::core::{Node, Tree, TreeError};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
 
 pub fn create_star_tree<I>(root: Node, nodes: I) -> Result<Tree, TreeError>
 where
@@ -29,9 +32,104 @@ where
     }
 }
 
+/// Builds a tree rooted at `root` from the map between the nodes and their children.
+/// Nodes without children do not need to be present as keys.
+///
+/// Returns `TopologyError` if the map does not encode a tree rooted at `root`,
+/// i.e., if a node has more than one parent, the root has a parent,
+/// or some nodes are not reachable from the root (e.g., they form a cycle).
+pub fn from_children_map(
+    root: Node,
+    children: HashMap<Node, Vec<Node>>,
+) -> Result<Tree, TreeError> {
+    // Each node can be a child at most once and the root cannot be a child.
+    let mut seen = HashSet::from([root]);
+    for child in children.values().flatten() {
+        if !seen.insert(*child) {
+            return Err(TreeError::TopologyError);
+        }
+    }
+
+    let mut tree = Tree::new(root);
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if let Some(node_children) = children.get(&node) {
+            for &child in node_children {
+                tree.add_node(node, child)?;
+                stack.push(child);
+            }
+        }
+    }
+
+    // All the nodes have to be reachable from the root.
+    if tree.len() != seen.len() || children.keys().any(|&node| !tree.contains(node)) {
+        return Err(TreeError::TopologyError);
+    }
+    if !tree.is_valid() {
+        return Err(TreeError::TopologyError);
+    }
+    Ok(tree)
+}
+
+/// Returns the number of rooted trees on `n` labeled nodes,
+/// where any of the nodes can be the root, which is `n^(n-1)`
+/// (Cayley's formula `n^(n-2)` for unrooted trees times `n` choices of the root).
+///
+/// Note that if the root label is fixed (as in mutation trees with
+/// a germline root and `m` mutations) the count is `num_rooted_labeled_trees(m + 1) / (m + 1)`.
+/// For `n = 0` returns 0.
+///
+/// The count fits in `u128` only for `n <= 27`; for larger `n` returns `None`.
+pub fn num_rooted_labeled_trees(n: usize) -> Option<u128> {
+    if n == 0 {
+        return Some(0);
+    }
+    let exponent = u32::try_from(n - 1).ok()?;
+    (n as u128).checked_pow(exponent)
+}
+
+/// Generates a random tree on nodes `0, 1, ..., n-1` (rooted at 0) for stress tests.
+/// First each node `k > 0` is attached to a node chosen uniformly from `0..k`,
+/// and then `n_moves` random moves (either `prune_and_reattach` to a valid
+/// new parent or `swap_labels`) are applied. In debug builds the validity
+/// of the tree is checked after every move.
+pub fn stress_tree<R: Rng>(rng: &mut R, n: usize, n_moves: usize) -> Tree {
+    assert!(n > 0, "The tree must contain at least one node.");
+
+    let mut tree = Tree::new(0);
+    for node in 1..n as Node {
+        let parent = rng.gen_range(0..node);
+        tree.add_node(parent, node).unwrap();
+    }
+    debug_assert!(tree.is_valid());
+
+    let nodes = tree.get_nodes();
+    for _ in 0..n_moves {
+        let node = *nodes.choose(rng).unwrap();
+        if rng.gen_bool(0.5) {
+            let descendants = tree.get_descendants(node);
+            let candidates: Vec<Node> = nodes
+                .iter()
+                .copied()
+                .filter(|&other| other != node && !descendants.contains(&other))
+                .collect();
+            // Only the root has no valid new parents.
+            if let Some(&new_parent) = candidates.choose(rng) {
+                tree.prune_and_reattach(node, new_parent).unwrap();
+            }
+        } else {
+            let other = *nodes.choose(rng).unwrap();
+            tree.swap_labels(node, other).unwrap();
+        }
+        debug_assert!(tree.is_valid());
+    }
+    tree
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tree::test_utils::simple_tree;
 
     mod test_create_star_tree {
         use super::*;
@@ -48,6 +146,142 @@ mod tests {
         }
     }
 
+    mod test_from_children_map {
+        use super::*;
+
+        #[test]
+        fn valid_map() {
+            let children =
+                HashMap::from([(0, vec![1, 10]), (1, vec![2]), (2, vec![3]), (10, vec![11])]);
+            let tree = from_children_map(0, children).unwrap();
+
+            assert_eq!(tree, simple_tree());
+        }
+
+        #[test]
+        fn single_node() {
+            let tree = from_children_map(5, HashMap::new()).unwrap();
+            assert_eq!(tree, Tree::new(5));
+        }
+
+        #[test]
+        fn two_parents() {
+            let children = HashMap::from([(0, vec![1, 2]), (1, vec![3]), (2, vec![3])]);
+            assert!(matches!(
+                from_children_map(0, children),
+                Err(TreeError::TopologyError)
+            ));
+        }
+
+        #[test]
+        fn root_with_parent() {
+            let children = HashMap::from([(0, vec![1]), (1, vec![0])]);
+            assert!(matches!(
+                from_children_map(0, children),
+                Err(TreeError::TopologyError)
+            ));
+        }
+
+        #[test]
+        fn unreachable_cycle() {
+            let children = HashMap::from([(0, vec![1]), (2, vec![3]), (3, vec![2])]);
+            assert!(matches!(
+                from_children_map(0, children),
+                Err(TreeError::TopologyError)
+            ));
+        }
+    }
+
+    mod test_num_rooted_labeled_trees {
+        use super::*;
+
+        /// Counts the assignments of parents to the nodes `0..n`
+        /// which form a rooted tree, by building each candidate.
+        fn brute_force_count(n: usize) -> u128 {
+            let mut count = 0;
+            for root in 0..n {
+                let others: Vec<usize> = (0..n).filter(|&i| i != root).collect();
+                let n_assignments = n.pow(others.len() as u32);
+
+                for code in 0..n_assignments {
+                    let mut parents = vec![0; n];
+                    let mut rest = code;
+                    for &node in others.iter() {
+                        parents[node] = rest % n;
+                        rest /= n;
+                    }
+
+                    // Add the nodes in the order in which their parents appear.
+                    let mut tree = Tree::new(root as Node);
+                    let mut added = true;
+                    while added {
+                        added = false;
+                        for &node in others.iter() {
+                            let parent = parents[node] as Node;
+                            if !tree.contains(node as Node) && tree.contains(parent) {
+                                tree.add_node(parent, node as Node).unwrap();
+                                added = true;
+                            }
+                        }
+                    }
+                    if tree.len() == n {
+                        count += 1;
+                    }
+                }
+            }
+            count
+        }
+
+        #[test]
+        fn small_values() {
+            assert_eq!(num_rooted_labeled_trees(0), Some(0));
+            assert_eq!(num_rooted_labeled_trees(1), Some(1));
+            assert_eq!(num_rooted_labeled_trees(2), Some(2));
+            assert_eq!(num_rooted_labeled_trees(3), Some(9));
+            assert_eq!(num_rooted_labeled_trees(4), Some(64));
+        }
+
+        #[test]
+        fn overflow_boundary() {
+            assert_eq!(num_rooted_labeled_trees(27), Some(27u128.pow(26)));
+            assert_eq!(num_rooted_labeled_trees(28), None);
+            assert_eq!(num_rooted_labeled_trees(usize::MAX), None);
+        }
+
+        #[test]
+        fn matches_enumeration() {
+            for n in 1..=5 {
+                assert_eq!(num_rooted_labeled_trees(n), Some(brute_force_count(n)));
+            }
+        }
+    }
+
+    mod test_stress_tree {
+        use super::*;
+        use rand::SeedableRng;
+
+        #[test]
+        fn size_and_validity() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+            for n in [1, 2, 10, 50] {
+                let tree = stress_tree(&mut rng, n, 100);
+                assert_eq!(tree.len(), n);
+                assert!(tree.is_valid());
+                assert_eq!(tree.get_nodes(), (0..n as Node).collect::<Vec<_>>());
+            }
+        }
+
+        #[test]
+        fn reproducible() {
+            let mut rng1 = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+            let mut rng2 = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+            assert_eq!(
+                stress_tree(&mut rng1, 20, 50),
+                stress_tree(&mut rng2, 20, 50)
+            );
+        }
+    }
+
     mod test_create_chain_tree {
         use super::*;
 
@@ -0,0 +1,212 @@
+/// Tree which remembers the order in which the children were added.
+use super::core::{Node, Tree, TreeError};
+use indexmap::IndexSet;
+use std::collections::HashMap;
+
+/// Variant of `Tree` in which the children of each node are stored
+/// in the order of insertion (rather than being sorted when traversed).
+/// This is useful for visualisation, when the original order matters.
+///
+/// The topology is kept in a `Tree` (which does all the checks),
+/// together with the order of the children of each internal node.
+#[derive(Debug, Clone)]
+pub struct OrderedTree {
+    tree: Tree,
+    order: HashMap<Node, IndexSet<Node>>,
+}
+
+/// Two ordered trees are equal if they have the same topology
+/// and the children of each node are in the same order.
+impl PartialEq for OrderedTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree
+            && self.order.len() == other.order.len()
+            && self.order.iter().all(|(node, children)| {
+                other
+                    .order
+                    .get(node)
+                    .is_some_and(|other_children| children.iter().eq(other_children.iter()))
+            })
+    }
+}
+
+impl Eq for OrderedTree {}
+
+impl OrderedTree {
+    /// Creates a new rooted tree with a single node `root`.
+    pub fn new(root: Node) -> Self {
+        OrderedTree {
+            tree: Tree::new(root),
+            order: HashMap::new(),
+        }
+    }
+
+    /// Converts `tree` into an ordered tree. As `Tree` does not
+    /// store the order of children, they are inserted in ascending order.
+    pub fn from_tree(tree: &Tree) -> Self {
+        let order = tree
+            .preorder()
+            .into_iter()
+            .map(|node| (node, tree.get_children(node)))
+            .filter(|(_, children)| !children.is_empty())
+            .map(|(node, children)| (node, children.into_iter().collect()))
+            .collect();
+        OrderedTree {
+            tree: tree.clone(),
+            order,
+        }
+    }
+
+    /// Converts the ordered tree into a `Tree` (forgetting the order of children).
+    pub fn to_tree(&self) -> Tree {
+        self.tree.clone()
+    }
+
+    /// Calculates the number of nodes in the tree
+    // A tree always contains its root, so it is never empty.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns the root of the tree.
+    pub fn get_root(&self) -> Node {
+        self.tree.get_root()
+    }
+
+    /// Returns true if `node` is already contained in the tree.
+    pub fn contains(&self, node: Node) -> bool {
+        self.tree.contains(node)
+    }
+
+    pub fn get_parent(&self, node: Node) -> Option<Node> {
+        self.tree.get_parent(node)
+    }
+
+    /// Returns the children of `node` in the order of insertion.
+    pub fn get_children(&self, node: Node) -> Vec<Node> {
+        self.order
+            .get(&node)
+            .map(|set| set.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Adds a child node to a parent, as the last of its children.
+    /// See `Tree::add_node` for the errors.
+    pub fn add_node(&mut self, parent: Node, child: Node) -> Result<(), TreeError> {
+        self.tree.add_node(parent, child)?;
+        self.order.entry(parent).or_default().insert(child);
+        Ok(())
+    }
+
+    /// Prunes and reattaches subtree rooted at `node` to `new_parent`
+    /// (as its last child), preserving the order of the remaining siblings.
+    /// See `Tree::prune_and_reattach` for the preconditions and the errors.
+    pub fn prune_and_reattach(&mut self, node: Node, new_parent: Node) -> Result<(), TreeError> {
+        let parent = self.tree.get_parent(node);
+        self.tree.prune_and_reattach(node, new_parent)?;
+
+        // The tree has been changed, so `node` was not the root and had a parent.
+        let parent = parent.unwrap();
+        if let Some(set) = self.order.get_mut(&parent) {
+            set.shift_remove(&node);
+            if set.is_empty() {
+                self.order.remove(&parent);
+            }
+        }
+        self.order.entry(new_parent).or_default().insert(node);
+
+        Ok(())
+    }
+
+    /// Returns the nodes in pre-order, visiting the children
+    /// in the order of insertion.
+    pub fn preorder(&self) -> Vec<Node> {
+        let mut order = Vec::with_capacity(self.len());
+        let mut stack = vec![self.get_root()];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            stack.extend(self.get_children(node).into_iter().rev());
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Generates a tree
+    /// 0–10–11
+    /// └─1–2
+    /// with children inserted in this order.
+    fn ordered_tree() -> OrderedTree {
+        let mut tree = OrderedTree::new(0);
+        tree.add_node(0, 10).unwrap();
+        tree.add_node(10, 11).unwrap();
+        tree.add_node(0, 1).unwrap();
+        tree.add_node(1, 2).unwrap();
+        tree
+    }
+
+    #[test]
+    fn traversal_follows_insertion_order() {
+        let tree = ordered_tree();
+        assert_eq!(tree.get_children(0), vec![10, 1]);
+        assert_eq!(tree.preorder(), vec![0, 10, 11, 1, 2]);
+    }
+
+    #[test]
+    fn prune_and_reattach_keeps_order() {
+        let mut tree = ordered_tree();
+        tree.add_node(0, 5).unwrap();
+        tree.prune_and_reattach(10, 2).unwrap();
+
+        assert_eq!(tree.get_children(0), vec![1, 5]);
+        assert_eq!(tree.preorder(), vec![0, 1, 2, 10, 11, 5]);
+
+        tree.prune_and_reattach(10, 0).unwrap();
+        assert_eq!(tree.get_children(0), vec![1, 5, 10]);
+    }
+
+    #[test]
+    fn prune_and_reattach_to_descendant() {
+        let mut tree = ordered_tree();
+        assert!(matches!(
+            tree.prune_and_reattach(10, 11),
+            Err(TreeError::TopologyError)
+        ));
+    }
+
+    #[test]
+    fn conversions() {
+        let tree = ordered_tree().to_tree();
+        let mut expected = Tree::new(0);
+        expected.add_node(0, 1).unwrap();
+        expected.add_node(1, 2).unwrap();
+        expected.add_node(0, 10).unwrap();
+        expected.add_node(10, 11).unwrap();
+        assert_eq!(tree, expected);
+
+        let ordered = OrderedTree::from_tree(&tree);
+        assert_eq!(ordered.get_children(0), vec![1, 10]);
+        assert_eq!(ordered.to_tree(), tree);
+    }
+
+    #[test]
+    fn equality_depends_on_order() {
+        let mut a = OrderedTree::new(0);
+        a.add_node(0, 1).unwrap();
+        a.add_node(0, 2).unwrap();
+
+        let mut b = OrderedTree::new(0);
+        b.add_node(0, 2).unwrap();
+        b.add_node(0, 1).unwrap();
+
+        assert_eq!(a.to_tree(), b.to_tree());
+        assert_ne!(a, b);
+
+        b.prune_and_reattach(2, 0).unwrap();
+        assert_eq!(a, b);
+    }
+}
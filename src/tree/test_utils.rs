@@ -0,0 +1,17 @@
+/// Fixtures shared by the tests of different modules.
+use super::core::Tree;
+
+/// Generates a tree
+/// 0–1–2–3
+/// └─10–11
+pub(crate) fn simple_tree() -> Tree {
+    let mut tree = Tree::new(0);
+
+    tree.add_node(0, 1).unwrap();
+    tree.add_node(1, 2).unwrap();
+    tree.add_node(2, 3).unwrap();
+
+    tree.add_node(0, 10).unwrap();
+    tree.add_node(10, 11).unwrap();
+    tree
+}